@@ -1,3 +1,5 @@
+use crate::releases::MirrorLayout;
+use clap::builder::{PossibleValue, StringValueParser, TypedValueParser};
 use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
@@ -10,7 +12,7 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub insecure: bool,
 
-    /// Path to rd-engine.json (for API commands)
+    /// Path to rd-engine.json (for API commands; defaults to `RH_CONFIG`, then the platform location)
     #[arg(long, global = true)]
     pub config: Option<PathBuf>,
 
@@ -39,6 +41,14 @@ pub struct Cli {
     )]
     pub download_timeout: u64,
 
+    /// Download k3s releases from this mirror instead of GitHub
+    #[arg(long, global = true, env = "RH_K3S_MIRROR", value_name = "URL")]
+    pub mirror: Option<String>,
+
+    /// How the mirror lays out release files (default: github)
+    #[arg(long, global = true, env = "RH_K3S_MIRROR_LAYOUT")]
+    pub mirror_layout: Option<MirrorLayout>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -52,7 +62,11 @@ pub enum Commands {
     },
 
     /// Run comprehensive diagnostic checks
-    Diagnose,
+    Diagnose {
+        /// Exit with code 1 if any check reports a warning
+        #[arg(long)]
+        strict: bool,
+    },
 
     /// Interact with Rancher Desktop HTTP API
     Api {
@@ -86,22 +100,92 @@ pub enum Commands {
     Version,
 
     /// Start the Rancher Desktop backend
-    Start,
+    Start {
+        /// Wait until the backend reports STARTED
+        #[arg(long)]
+        wait: bool,
+
+        /// Maximum time to wait in seconds
+        #[arg(long, value_name = "SECS", default_value = "300")]
+        wait_timeout: u64,
+    },
 
     /// Stop the Rancher Desktop backend
-    Stop,
+    Stop {
+        /// Wait until the backend reports STOPPED
+        #[arg(long)]
+        wait: bool,
+
+        /// Maximum time to wait in seconds
+        #[arg(long, value_name = "SECS", default_value = "300")]
+        wait_timeout: u64,
+    },
 
     /// Restart the Rancher Desktop backend
-    Restart,
+    Restart {
+        /// Return once the start is requested instead of waiting for STARTED
+        #[arg(long)]
+        no_wait: bool,
+
+        /// Maximum time to wait for each transition in seconds
+        #[arg(long, value_name = "SECS", default_value = "300")]
+        wait_timeout: u64,
+    },
 
     /// Show backend status
-    Status,
+    Status {
+        /// Include Kubernetes version, container engine and k3s cache state
+        #[arg(long, conflicts_with = "watch")]
+        details: bool,
+
+        /// Keep polling and print each state transition until interrupted
+        #[arg(long)]
+        watch: bool,
+
+        /// Polling interval for --watch in seconds
+        #[arg(
+            long,
+            value_name = "SECS",
+            default_value = "2",
+            requires = "watch",
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        interval: u64,
+    },
+
+    /// Wait until Rancher Desktop is ready to use
+    Wait {
+        /// Conditions to wait for (the API is always waited for first)
+        #[arg(
+            long = "for",
+            value_name = "CONDITION",
+            value_delimiter = ',',
+            default_values_t = [WaitCondition::Api, WaitCondition::Backend, WaitCondition::Kubernetes]
+        )]
+        conditions: Vec<WaitCondition>,
+
+        /// Maximum time to wait in seconds
+        #[arg(long, value_name = "SECS", default_value = "300")]
+        wait_timeout: u64,
+    },
 
     /// View and modify Rancher Desktop settings
     Settings {
         #[command(subcommand)]
         command: Option<SettingsCommands>,
     },
+
+    /// Generate a shell completion script
+    Completions {
+        /// Shell to generate completions for
+        shell: clap_complete::Shell,
+    },
+
+    /// Save, switch between and manage named settings profiles
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
 }
 
 #[derive(Subcommand)]
@@ -118,13 +202,70 @@ pub enum CacheCommands {
         /// Continue even if checksum verification fails
         #[arg(long, short)]
         force: bool,
+
+        /// Times to retry a download after a timeout, dropped connection or server error
+        #[arg(long, default_value = "3")]
+        retries: u32,
+
+        /// Seconds to wait before the first retry; doubles for each further retry
+        #[arg(long, value_name = "SECONDS", default_value = "1")]
+        retry_delay: u64,
+    },
+
+    /// Pack a cached k3s version into a bundle for offline machines
+    #[command(disable_version_flag = true)]
+    Export {
+        /// Cached k3s version to export (e.g., v1.33.3+k3s1)
+        version: String,
+
+        /// Bundle file to write (default: k3s-<version>-<arch>.tar)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Install a k3s version into the cache from a bundle made by 'rh cache export'
+    Import {
+        /// Bundle file to import
+        bundle: PathBuf,
+
+        /// Replace the version if it is already cached
+        #[arg(long, short)]
+        force: bool,
+    },
+
+    /// Re-check cached files against their checksums
+    #[command(disable_version_flag = true)]
+    Verify {
+        /// Cached k3s version to verify (e.g., v1.33.3+k3s1)
+        #[arg(required_unless_present = "all")]
+        version: Option<String>,
+
+        /// Verify every cached version
+        #[arg(long, conflicts_with = "version")]
+        all: bool,
+
+        /// Download corrupt or missing files again
+        #[arg(long)]
+        repair: bool,
+
+        /// Times to retry a repair download after a timeout, dropped connection or server error
+        #[arg(long, default_value = "3", requires = "repair")]
+        retries: u32,
+
+        /// Seconds to wait before the first retry; doubles for each further retry
+        #[arg(long, value_name = "SECONDS", default_value = "1", requires = "repair")]
+        retry_delay: u64,
     },
 }
 
 #[derive(Subcommand)]
 pub enum CertsCommands {
     /// Test SSL connectivity to required domains
-    Check,
+    Check {
+        /// Exit with code 1 if a corporate SSL inspection proxy is detected
+        #[arg(long)]
+        strict: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -132,20 +273,267 @@ pub enum SettingsCommands {
     /// Get a specific setting value
     Get {
         /// Setting path using dot notation (e.g., kubernetes.version)
+        #[arg(value_parser = SettingPathParser, hide_possible_values = true)]
         path: String,
     },
 
-    /// Set a setting value
+    /// Describe a setting: type, allowed values, default, restart and platforms
+    Explain {
+        /// Documented setting path; lists all documented settings if omitted
+        #[arg(value_parser = SettingPathParser, hide_possible_values = true)]
+        path: Option<String>,
+    },
+
+    /// Set one or more setting values in a single update
+    #[command(
+        override_usage = "rh settings set <PATH> <VALUE>\n       rh settings set <PATH=VALUE>... [--from-stdin]"
+    )]
     Set {
-        /// Setting path using dot notation (e.g., kubernetes.version)
+        /// Setting path, or a first `PATH=VALUE` assignment (e.g., kubernetes.enabled=true)
+        #[arg(
+            value_name = "PATH",
+            required_unless_present = "from_stdin",
+            value_parser = SettingPathParser,
+            hide_possible_values = true
+        )]
+        path: Option<String>,
+
+        /// The value for PATH, or further `PATH=VALUE` assignments
+        #[arg(value_name = "ASSIGNMENT")]
+        assignments: Vec<String>,
+
+        /// Also read `PATH=VALUE` lines from stdin (blank lines and # comments are ignored)
+        #[arg(long)]
+        from_stdin: bool,
+
+        /// Allow creating settings that do not exist yet (e.g., a new key in WSL.integrations)
+        #[arg(long)]
+        allow_new: bool,
+
+        /// Validate the change and show what would change without applying it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Remove an array element (Rancher Desktop cannot delete object keys)
+    Unset {
+        /// Path of an array element (e.g., containerEngine.allowedImages.patterns[0])
+        #[arg(value_parser = SettingPathParser, hide_possible_values = true)]
+        path: String,
+
+        /// Validate the change and show what would change without applying it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Append a value to a list setting if it is not already present
+    Add {
+        /// Path of the list setting (e.g., containerEngine.allowedImages.patterns)
+        #[arg(value_parser = SettingPathParser, hide_possible_values = true)]
         path: String,
 
-        /// Value to set
+        /// Value to append
         value: String,
+
+        /// Validate the change and show what would change without applying it
+        #[arg(long)]
+        dry_run: bool,
     },
 
-    /// Reset all settings to defaults (factory reset)
-    Reset,
+    /// Remove every occurrence of a value from a list setting
+    Remove {
+        /// Path of the list setting (e.g., containerEngine.allowedImages.patterns)
+        #[arg(value_parser = SettingPathParser, hide_possible_values = true)]
+        path: String,
+
+        /// Value to remove
+        value: String,
+
+        /// Validate the change and show what would change without applying it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Reset all settings to defaults (factory reset), backing them up first
+    Reset {
+        /// Do not back up the current settings before resetting
+        #[arg(long)]
+        no_backup: bool,
+    },
+
+    /// Manage settings backups taken before factory resets
+    Backups {
+        #[command(subcommand)]
+        command: BackupsCommands,
+    },
+
+    /// Restore settings from a backup taken before a factory reset
+    Restore {
+        /// Backup name (see `rh settings backups list`) or path to a backup file
+        backup: String,
+
+        /// Validate the backup and show what would change without applying it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Export all settings to a file (or stdout)
+    Export {
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// File format (defaults to the output file's extension, or JSON)
+        #[arg(long)]
+        format: Option<SettingsFormat>,
+    },
+
+    /// Validate and apply settings from a JSON or YAML file
+    Import {
+        /// Settings file to apply
+        file: PathBuf,
+
+        /// File format (defaults to the file's extension, or JSON)
+        #[arg(long)]
+        format: Option<SettingsFormat>,
+
+        /// Validate the file and show what would change without applying it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Edit settings in $EDITOR, then validate, review and apply them
+    Edit {
+        /// Format to edit in
+        #[arg(long, default_value = "yaml")]
+        format: SettingsFormat,
+    },
+
+    /// Show what would change if a settings file were imported
+    Diff {
+        /// Settings file to compare against the current settings
+        file: PathBuf,
+
+        /// File format (defaults to the file's extension, or JSON)
+        #[arg(long)]
+        format: Option<SettingsFormat>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BackupsCommands {
+    /// List settings backups, newest first
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum ProfileCommands {
+    /// Save the current settings as a profile
+    Save {
+        /// Profile name (letters, digits, '-', '_' and '.')
+        name: String,
+
+        /// Overwrite an existing profile with the same name
+        #[arg(long, short)]
+        force: bool,
+    },
+
+    /// List saved profiles
+    List,
+
+    /// Show the settings saved in a profile
+    Show {
+        /// Profile name
+        name: String,
+    },
+
+    /// Apply a saved profile
+    Apply {
+        /// Profile name
+        name: String,
+
+        /// Validate the profile and show what would change without applying it
+        #[arg(long, conflicts_with = "restart")]
+        dry_run: bool,
+
+        /// Restart the backend if the profile requires it
+        #[arg(long)]
+        restart: bool,
+
+        /// Maximum time to wait for each restart transition in seconds
+        #[arg(long, value_name = "SECS", default_value = "300", requires = "restart")]
+        wait_timeout: u64,
+    },
+
+    /// Delete a saved profile
+    Delete {
+        /// Profile name
+        name: String,
+    },
+}
+
+/// File format for exported and imported settings
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SettingsFormat {
+    Json,
+    Yaml,
+}
+
+impl SettingsFormat {
+    /// Guess the format from a file extension, defaulting to JSON
+    #[must_use]
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => {
+                Self::Yaml
+            }
+            _ => Self::Json,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum WaitCondition {
+    /// rd-engine.json exists and the API responds
+    Api,
+    /// The backend reports STARTED
+    Backend,
+    /// The k3s API server reports ready (satisfied immediately if disabled in settings)
+    Kubernetes,
+}
+
+impl std::fmt::Display for WaitCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WaitCondition::Api => write!(f, "api"),
+            WaitCondition::Backend => write!(f, "backend"),
+            WaitCondition::Kubernetes => write!(f, "kubernetes"),
+        }
+    }
+}
+
+/// Accepts any setting path, but offers the documented paths to shell
+/// completion scripts
+#[derive(Clone, Copy, Debug)]
+pub struct SettingPathParser;
+
+impl TypedValueParser for SettingPathParser {
+    type Value = String;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        StringValueParser::new().parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(
+            crate::commands::settings::docs::paths().map(PossibleValue::new),
+        ))
+    }
 }
 
 #[derive(Clone, ValueEnum)]
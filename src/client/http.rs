@@ -1,7 +1,3 @@
-// This module provides HTTP client infrastructure that will be used by command implementations.
-// Allow dead_code during infrastructure phase - will be removed when commands are implemented.
-#![allow(dead_code)]
-
 //! HTTP client with SSL certificate bypass support.
 //!
 //! # Why Certificate Bypass?
@@ -24,6 +20,7 @@
 use crate::constants::extract_domain;
 use anyhow::{Context, Result};
 use dialoguer::Confirm;
+use reqwest::header::HeaderMap;
 use reqwest::Client;
 use std::io::IsTerminal;
 use thiserror::Error;
@@ -35,10 +32,26 @@ pub enum HttpClientError {
     CertificateError { domain: String, reason: String },
     #[error("Connection refused - is Rancher Desktop running?")]
     ConnectionRefused,
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+    #[error("Failed to build HTTP client: {0}")]
+    ClientBuild(String),
     #[error("Request failed: {0}")]
     RequestFailed(String),
 }
 
+impl From<reqwest::Error> for HttpClientError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_connect() {
+            Self::ConnectionRefused
+        } else if error.is_timeout() {
+            Self::Timeout(error.to_string())
+        } else {
+            Self::RequestFailed(error.to_string())
+        }
+    }
+}
+
 /// Default timeout for API requests (30 seconds)
 pub const DEFAULT_API_TIMEOUT_SECS: u64 = 30;
 
@@ -68,6 +81,7 @@ impl Default for HttpClientConfig {
 
 impl HttpClientConfig {
     /// Create a new config for API requests
+    #[must_use]
     pub fn new(insecure: bool) -> Self {
         Self {
             insecure,
@@ -77,6 +91,7 @@ impl HttpClientConfig {
     }
 
     /// Create a new config with custom timeout
+    #[must_use]
     pub fn with_timeout(insecure: bool, timeout_secs: u64) -> Self {
         Self {
             insecure,
@@ -86,6 +101,7 @@ impl HttpClientConfig {
     }
 
     /// Create a config suitable for large file downloads
+    #[must_use]
     pub fn for_downloads(insecure: bool) -> Self {
         Self {
             insecure,
@@ -95,6 +111,7 @@ impl HttpClientConfig {
     }
 
     /// Create a config for downloads with custom timeout
+    #[must_use]
     pub fn for_downloads_with_timeout(insecure: bool, timeout_secs: u64) -> Self {
         Self {
             insecure,
@@ -135,24 +152,27 @@ pub fn build_insecure_client() -> Result<Client> {
     build_client(&HttpClientConfig::new(true))
 }
 
-/// Attempt a request, handling certificate errors with optional interactive prompt
+/// Attempt a GET request with extra `headers` (e.g. `Range`), handling
+/// certificate errors with optional interactive prompt
 pub async fn request_with_cert_handling(
     url: &str,
+    headers: &HeaderMap,
     config: &HttpClientConfig,
 ) -> Result<reqwest::Response> {
     // First try with the configured client
     let client = build_client(config)?;
 
-    match client.get(url).send().await {
+    match client.get(url).headers(headers.clone()).send().await {
         Ok(response) => Ok(response),
         Err(e) => {
             // Check if this is a certificate error
             if is_certificate_error(&e) {
-                handle_certificate_error(url, &e, config).await
-            } else if e.is_connect() {
-                Err(HttpClientError::ConnectionRefused.into())
+                handle_certificate_error(url, headers, &e, config).await
             } else {
-                Err(HttpClientError::RequestFailed(e.to_string()).into())
+                // Keep the reqwest error so callers can tell a DNS failure or
+                // an invalid URL from a dropped connection
+                let domain = extract_domain_or_unknown(url);
+                Err(anyhow::Error::new(e).context(format!("Request to {domain} failed")))
             }
         }
     }
@@ -171,6 +191,7 @@ fn is_certificate_error(error: &reqwest::Error) -> bool {
 /// Handle certificate errors with optional interactive prompt
 async fn handle_certificate_error(
     url: &str,
+    headers: &HeaderMap,
     error: &reqwest::Error,
     config: &HttpClientConfig,
 ) -> Result<reqwest::Response> {
@@ -215,6 +236,7 @@ async fn handle_certificate_error(
             let insecure_client = build_insecure_client()?;
             return insecure_client
                 .get(url)
+                .headers(headers.clone())
                 .send()
                 .await
                 .context("Request failed even with certificate bypass");
@@ -281,6 +303,7 @@ pub const KNOWN_PROXY_ISSUERS: &[&str] = &[
 ];
 
 /// Check if a certificate issuer looks like a corporate proxy
+#[must_use]
 pub fn is_proxy_issuer(issuer: &str) -> bool {
     let lower = issuer.to_lowercase();
     KNOWN_PROXY_ISSUERS
@@ -0,0 +1,201 @@
+//! Minimal client for the Kubernetes API of Rancher Desktop's k3s cluster.
+//!
+//! Rancher Desktop writes a `rancher-desktop` context to the user's kubeconfig
+//! with client certificate credentials. Only what is needed to probe the API
+//! server's `/readyz` endpoint is read from it.
+
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use reqwest::{Certificate, Client, Identity};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// kubeconfig context Rancher Desktop creates for its cluster
+pub const RANCHER_DESKTOP_CONTEXT: &str = "rancher-desktop";
+
+#[derive(Debug, Deserialize)]
+struct Kubeconfig {
+    #[serde(default)]
+    contexts: Vec<Named<ContextEntry>>,
+    #[serde(default)]
+    clusters: Vec<Named<ClusterEntry>>,
+    #[serde(default)]
+    users: Vec<Named<UserEntry>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Named<T> {
+    name: String,
+    #[serde(alias = "context", alias = "cluster", alias = "user")]
+    value: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContextEntry {
+    cluster: String,
+    user: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ClusterEntry {
+    server: String,
+    certificate_authority_data: Option<String>,
+    #[serde(default)]
+    insecure_skip_tls_verify: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct UserEntry {
+    client_certificate_data: Option<String>,
+    client_key_data: Option<String>,
+    token: Option<String>,
+}
+
+/// A client for one kubeconfig context
+#[derive(Debug)]
+pub struct KubeClient {
+    server: String,
+    token: Option<String>,
+    client: Client,
+}
+
+/// The kubeconfig kubectl would use: the first `KUBECONFIG` entry, or `~/.kube/config`
+pub fn kubeconfig_path() -> Result<PathBuf> {
+    if let Some(paths) = std::env::var_os("KUBECONFIG").filter(|p| !p.is_empty()) {
+        if let Some(path) = std::env::split_paths(&paths).next() {
+            return Ok(path);
+        }
+    }
+
+    dirs::home_dir()
+        .map(|home| home.join(".kube").join("config"))
+        .context("Could not determine home directory")
+}
+
+impl KubeClient {
+    /// Build a client for `context` in the kubeconfig at `path`
+    pub fn load(path: &Path, context: &str, insecure: bool, timeout_secs: u64) -> Result<Self> {
+        let yaml = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read kubeconfig {}", path.display()))?;
+        Self::from_kubeconfig(&yaml, context, insecure, timeout_secs)
+    }
+
+    fn from_kubeconfig(
+        yaml: &str,
+        context: &str,
+        insecure: bool,
+        timeout_secs: u64,
+    ) -> Result<Self> {
+        let config: Kubeconfig =
+            serde_yaml::from_str(yaml).context("Failed to parse kubeconfig")?;
+
+        let entry = &config
+            .contexts
+            .iter()
+            .find(|c| c.name == context)
+            .with_context(|| format!("No '{context}' context in kubeconfig"))?
+            .value;
+        let cluster = &config
+            .clusters
+            .iter()
+            .find(|c| c.name == entry.cluster)
+            .with_context(|| format!("No '{}' cluster in kubeconfig", entry.cluster))?
+            .value;
+        let user = config
+            .users
+            .into_iter()
+            .find(|u| u.name == entry.user)
+            .map(|u| u.value)
+            .unwrap_or_default();
+
+        let mut builder = Client::builder()
+            .timeout(std::time::Duration::from_secs(timeout_secs))
+            .danger_accept_invalid_certs(insecure || cluster.insecure_skip_tls_verify);
+
+        if let Some(ca) = &cluster.certificate_authority_data {
+            builder = builder.add_root_certificate(
+                Certificate::from_pem(&decode(ca)?).context("Invalid cluster CA certificate")?,
+            );
+        }
+        if let (Some(cert), Some(key)) = (&user.client_certificate_data, &user.client_key_data) {
+            let pem = [decode(cert)?, decode(key)?].join(&b'\n');
+            builder =
+                builder.identity(Identity::from_pem(&pem).context("Invalid client certificate")?);
+        }
+
+        Ok(Self {
+            server: cluster.server.trim_end_matches('/').to_string(),
+            token: user.token,
+            client: builder
+                .build()
+                .context("Failed to build Kubernetes client")?,
+        })
+    }
+
+    /// API server URL
+    #[must_use]
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    /// Succeeds once the API server reports itself ready
+    pub async fn readyz(&self) -> Result<()> {
+        let mut request = self.client.get(format!("{}/readyz", self.server));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        Err(anyhow!("/readyz returned {status}: {}", body.trim()))
+    }
+}
+
+fn decode(data: &str) -> Result<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .context("Invalid base64 data in kubeconfig")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KUBECONFIG: &str = r"
+apiVersion: v1
+kind: Config
+current-context: other
+clusters:
+  - name: rancher-desktop
+    cluster:
+      server: https://127.0.0.1:6443/
+      insecure-skip-tls-verify: true
+contexts:
+  - name: rancher-desktop
+    context:
+      cluster: rancher-desktop
+      user: rancher-desktop
+users:
+  - name: rancher-desktop
+    user:
+      token: secret
+";
+
+    #[test]
+    fn test_from_kubeconfig() {
+        let client =
+            KubeClient::from_kubeconfig(KUBECONFIG, RANCHER_DESKTOP_CONTEXT, false, 5).unwrap();
+        assert_eq!(client.server(), "https://127.0.0.1:6443");
+        assert_eq!(client.token.as_deref(), Some("secret"));
+
+        let err = KubeClient::from_kubeconfig(KUBECONFIG, "minikube", false, 5).unwrap_err();
+        assert!(err.to_string().contains("minikube"), "{err}");
+    }
+}
@@ -1 +1,3 @@
 pub mod http;
+pub mod kube;
+pub mod rd;
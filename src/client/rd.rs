@@ -0,0 +1,360 @@
+//! Typed client for the Rancher Desktop HTTP API.
+//!
+//! Wraps the connection details from `rd-engine.json` and exposes one method
+//! per API operation, so callers don't have to build URLs, attach the basic
+//! auth header, or check response status codes themselves.
+
+use crate::cli::Cli;
+use crate::client::http::{build_client, HttpClientConfig, HttpClientError};
+use crate::config::{ConfigError, RdEngineConfig};
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use tracing::debug;
+
+/// Endpoint for reading and changing the backend state
+const BACKEND_STATE_ENDPOINT: &str = "/v1/backend_state";
+
+/// Endpoint for reading and replacing settings
+const SETTINGS_ENDPOINT: &str = "/v1/settings";
+
+/// Endpoint listing the settings locked by a deployment profile
+const LOCKED_SETTINGS_ENDPOINT: &str = "/v1/settings/locked";
+
+/// Endpoint for validating settings without applying them
+const PROPOSE_SETTINGS_ENDPOINT: &str = "/v1/propose_settings";
+
+/// Endpoint for resetting Rancher Desktop to factory defaults
+const FACTORY_RESET_ENDPOINT: &str = "/v1/factory_reset";
+
+/// Endpoint for shutting down Rancher Desktop
+const SHUTDOWN_ENDPOINT: &str = "/v1/shutdown";
+
+#[derive(Error, Debug)]
+pub enum RdClientError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Http(#[from] HttpClientError),
+    #[error("Failed to {operation}: HTTP {status}{}", format_body(.body))]
+    Status {
+        operation: &'static str,
+        status: StatusCode,
+        body: String,
+    },
+    #[error("Failed to parse {operation} response: {reason}")]
+    InvalidResponse {
+        operation: &'static str,
+        reason: String,
+    },
+}
+
+fn format_body(body: &str) -> String {
+    if body.is_empty() {
+        String::new()
+    } else {
+        format!(" - {body}")
+    }
+}
+
+/// Backend states as returned by the Rancher Desktop API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum BackendState {
+    Started,
+    Starting,
+    Stopped,
+    Stopping,
+    Error,
+    Disabled,
+    #[serde(other)]
+    Unknown,
+}
+
+impl std::fmt::Display for BackendState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Started => write!(f, "STARTED"),
+            Self::Starting => write!(f, "STARTING"),
+            Self::Stopped => write!(f, "STOPPED"),
+            Self::Stopping => write!(f, "STOPPING"),
+            Self::Error => write!(f, "ERROR"),
+            Self::Disabled => write!(f, "DISABLED"),
+            Self::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+impl BackendState {
+    /// Parse state from API response string
+    fn from_str(s: &str) -> Self {
+        match s.trim().trim_matches('"').to_uppercase().as_str() {
+            "STARTED" => Self::Started,
+            "STARTING" => Self::Starting,
+            "STOPPED" => Self::Stopped,
+            "STOPPING" => Self::Stopping,
+            "ERROR" => Self::Error,
+            "DISABLED" => Self::Disabled,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Result of validating settings via `/v1/propose_settings`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsProposal {
+    /// Validation errors keyed by setting, if any
+    #[serde(default)]
+    pub errors: Option<Value>,
+    /// Whether applying the settings requires a backend restart
+    #[serde(default)]
+    pub requires_restart: bool,
+}
+
+impl SettingsProposal {
+    /// Whether the proposal reported any validation errors
+    #[must_use]
+    pub fn has_errors(&self) -> bool {
+        matches!(&self.errors, Some(Value::Object(errors)) if !errors.is_empty())
+    }
+}
+
+/// Client for the Rancher Desktop HTTP API
+#[derive(Debug, Clone)]
+pub struct RdClient {
+    client: Client,
+    config: RdEngineConfig,
+}
+
+impl RdClient {
+    /// Create a client from already-loaded connection info
+    pub fn new(
+        config: RdEngineConfig,
+        http_config: &HttpClientConfig,
+    ) -> Result<Self, RdClientError> {
+        let client = build_client(http_config)
+            .map_err(|e| HttpClientError::ClientBuild(format!("{e:#}")))?;
+        Ok(Self { client, config })
+    }
+
+    /// Create a client using the CLI's `--config` (or `RH_CONFIG`), `--insecure` and `--timeout` options
+    pub fn from_cli(cli: &Cli) -> Result<Self, RdClientError> {
+        let config = RdEngineConfig::load_from(cli.config.as_deref())?;
+        Self::new(
+            config,
+            &HttpClientConfig::with_timeout(cli.insecure, cli.timeout),
+        )
+    }
+
+    /// Connection info this client was created with
+    #[must_use]
+    pub fn config(&self) -> &RdEngineConfig {
+        &self.config
+    }
+
+    /// Get the current backend state
+    pub async fn backend_state(&self) -> Result<BackendState, RdClientError> {
+        let operation = "get backend state";
+        let response = self
+            .send(self.request(Method::GET, BACKEND_STATE_ENDPOINT), operation)
+            .await?;
+        let body = read_text(response, operation).await?;
+        Ok(BackendState::from_str(&body))
+    }
+
+    /// Request a new backend state (e.g. `Started` or `Stopped`)
+    pub async fn set_backend_state(&self, state: &BackendState) -> Result<(), RdClientError> {
+        let request = self
+            .request(Method::PUT, BACKEND_STATE_ENDPOINT)
+            .header("Content-Type", "application/json")
+            .body(format!("\"{state}\""));
+        self.send(request, "set backend state").await?;
+        Ok(())
+    }
+
+    /// Fetch the full settings document
+    pub async fn settings(&self) -> Result<Value, RdClientError> {
+        let operation = "fetch settings";
+        let response = self
+            .send(self.request(Method::GET, SETTINGS_ENDPOINT), operation)
+            .await?;
+        read_json(response, operation).await
+    }
+
+    /// Fetch the settings locked by a deployment profile.
+    ///
+    /// The document mirrors the settings, with `true` for each locked setting.
+    pub async fn locked_settings(&self) -> Result<Value, RdClientError> {
+        let operation = "fetch locked settings";
+        let response = self
+            .send(
+                self.request(Method::GET, LOCKED_SETTINGS_ENDPOINT),
+                operation,
+            )
+            .await?;
+        read_json(response, operation).await
+    }
+
+    /// Validate settings without applying them
+    pub async fn propose_settings(
+        &self,
+        settings: &Value,
+    ) -> Result<SettingsProposal, RdClientError> {
+        let operation = "propose settings";
+        let request = self
+            .request(Method::PUT, PROPOSE_SETTINGS_ENDPOINT)
+            .json(settings);
+        let response = self.send(request, operation).await?;
+        read_json(response, operation).await
+    }
+
+    /// Replace the settings document
+    pub async fn put_settings(&self, settings: &Value) -> Result<(), RdClientError> {
+        let request = self.request(Method::PUT, SETTINGS_ENDPOINT).json(settings);
+        self.send(request, "update settings").await?;
+        Ok(())
+    }
+
+    /// Reset Rancher Desktop to factory defaults
+    pub async fn factory_reset(&self) -> Result<(), RdClientError> {
+        self.send(
+            self.request(Method::PUT, FACTORY_RESET_ENDPOINT),
+            "reset settings",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Shut down Rancher Desktop
+    pub async fn shutdown(&self) -> Result<(), RdClientError> {
+        self.send(self.request(Method::PUT, SHUTDOWN_ENDPOINT), "shut down")
+            .await?;
+        Ok(())
+    }
+
+    /// Send an arbitrary authenticated request without checking the status code.
+    ///
+    /// Used by `rh api`, which reports the response body even for failures.
+    pub async fn raw_request(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<String>,
+    ) -> Result<Response, RdClientError> {
+        let mut request = self
+            .request(method, endpoint)
+            .header("Authorization", self.config.basic_auth());
+        if let Some(body) = body {
+            request = request
+                .header("Content-Type", "application/json")
+                .body(body);
+        }
+        Ok(request.send().await.map_err(HttpClientError::from)?)
+    }
+
+    fn request(&self, method: Method, endpoint: &str) -> RequestBuilder {
+        let url = self.config.api_url(endpoint);
+        debug!("{} {}", method, url);
+        self.client.request(method, url)
+    }
+
+    async fn send(
+        &self,
+        request: RequestBuilder,
+        operation: &'static str,
+    ) -> Result<Response, RdClientError> {
+        let response = request
+            .header("Authorization", self.config.basic_auth())
+            .send()
+            .await
+            .map_err(HttpClientError::from)?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(RdClientError::Status {
+                operation,
+                status,
+                body,
+            });
+        }
+
+        Ok(response)
+    }
+}
+
+async fn read_text(response: Response, operation: &'static str) -> Result<String, RdClientError> {
+    response
+        .text()
+        .await
+        .map_err(|e| RdClientError::InvalidResponse {
+            operation,
+            reason: e.to_string(),
+        })
+}
+
+async fn read_json<T: serde::de::DeserializeOwned>(
+    response: Response,
+    operation: &'static str,
+) -> Result<T, RdClientError> {
+    response
+        .json()
+        .await
+        .map_err(|e| RdClientError::InvalidResponse {
+            operation,
+            reason: e.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_state_from_str() {
+        assert_eq!(BackendState::from_str("\"STARTED\""), BackendState::Started);
+        assert_eq!(BackendState::from_str("stopped\n"), BackendState::Stopped);
+        assert_eq!(BackendState::from_str("\"bogus\""), BackendState::Unknown);
+    }
+
+    #[test]
+    fn test_settings_proposal_errors() {
+        let proposal: SettingsProposal = serde_json::from_value(serde_json::json!({
+            "errors": {"kubernetes.version": "invalid"},
+            "requiresRestart": true
+        }))
+        .unwrap();
+        assert!(proposal.has_errors());
+        assert!(proposal.requires_restart);
+
+        let proposal: SettingsProposal =
+            serde_json::from_value(serde_json::json!({"errors": null})).unwrap();
+        assert!(!proposal.has_errors());
+        assert!(!proposal.requires_restart);
+    }
+
+    #[test]
+    fn test_status_error_message() {
+        let err = RdClientError::Status {
+            operation: "set backend state",
+            status: StatusCode::BAD_REQUEST,
+            body: "invalid state".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Failed to set backend state: HTTP 400 Bad Request - invalid state"
+        );
+
+        let err = RdClientError::Status {
+            operation: "get backend state",
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            body: String::new(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Failed to get backend state: HTTP 500 Internal Server Error"
+        );
+    }
+}
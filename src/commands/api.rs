@@ -4,9 +4,9 @@
 //! JSON request bodies, and pretty-printed or raw output.
 
 use crate::cli::{Cli, HttpMethod};
-use crate::client::http::{build_client, HttpClientConfig};
-use crate::config::RdEngineConfig;
+use crate::client::rd::RdClient;
 use anyhow::{Context, Result};
+use reqwest::Method;
 use std::fs;
 use std::path::PathBuf;
 use tracing::{debug, info};
@@ -22,42 +22,28 @@ pub async fn run(
 ) -> Result<()> {
     info!("API request: {} {}", method, endpoint);
 
-    // Load configuration
-    let config = RdEngineConfig::load()
+    let client = RdClient::from_cli(cli)
         .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
-
-    // Build the URL
-    let url = config.api_url(endpoint);
-    debug!("Full URL: {}", url);
+    debug!("Full URL: {}", client.config().api_url(endpoint));
 
     // Get request body from --body or --input
     let request_body = get_request_body(body, input)?;
-
-    // Build HTTP client
-    let client_config = HttpClientConfig::with_timeout(cli.insecure, cli.timeout);
-    let client = build_client(&client_config)?;
-
-    // Build and send the request
-    let mut request = match method {
-        HttpMethod::Get => client.get(&url),
-        HttpMethod::Post => client.post(&url),
-        HttpMethod::Put => client.put(&url),
-        HttpMethod::Delete => client.delete(&url),
-    };
-
-    // Add authorization header
-    request = request.header("Authorization", config.basic_auth());
-
-    // Add body if provided
     if let Some(body) = &request_body {
         debug!("Request body: {}", body);
-        request = request
-            .header("Content-Type", "application/json")
-            .body(body.clone());
     }
 
+    let method = match method {
+        HttpMethod::Get => Method::GET,
+        HttpMethod::Post => Method::POST,
+        HttpMethod::Put => Method::PUT,
+        HttpMethod::Delete => Method::DELETE,
+    };
+
     // Send the request
-    let response = request.send().await.context("Failed to send API request")?;
+    let response = client
+        .raw_request(method, endpoint, request_body)
+        .await
+        .context("Failed to send API request")?;
 
     let status = response.status();
     debug!("Response status: {}", status);
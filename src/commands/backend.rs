@@ -4,55 +4,56 @@
 //! the /`v1/backend_state` API endpoint.
 
 use crate::cli::Cli;
-use crate::client::http::{build_client, HttpClientConfig};
-use crate::config::RdEngineConfig;
+pub use crate::client::rd::BackendState;
+use crate::client::rd::{RdClient, RdClientError};
+use crate::commands::cache::{find_cached_version, scan_cache_versions};
+use crate::commands::version::RancherDesktopInfo;
+use crate::exit::ExitStatus;
+use crate::paths::{k3s_cache_dir, ranch_hand_data_dir};
 use anyhow::{Context, Result};
+use chrono::{DateTime, Local, SecondsFormat};
 use colored::Colorize;
-use serde::Serialize;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::time::{Duration, Instant};
+use thiserror::Error;
 use tracing::{debug, info};
 
-/// Backend states as returned by the Rancher Desktop API
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
-#[serde(rename_all = "UPPERCASE")]
-pub enum BackendState {
-    Started,
-    Starting,
-    Stopped,
-    Stopping,
-    Error,
-    Disabled,
-    #[serde(other)]
-    Unknown,
-}
-
-impl std::fmt::Display for BackendState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// How often to poll the backend state while waiting for a transition
+const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Errors from waiting on a backend state transition
+#[derive(Error, Debug)]
+pub enum WaitError {
+    #[error("Timed out after {}s waiting for backend to reach {target} (last state: {last_state})", .elapsed.as_secs())]
+    Timeout {
+        target: BackendState,
+        last_state: BackendState,
+        elapsed: Duration,
+    },
+    #[error("Timed out after {}s waiting for {condition} ({status})", .elapsed.as_secs())]
+    ConditionTimeout {
+        condition: String,
+        status: String,
+        elapsed: Duration,
+    },
+    #[error("Backend entered the ERROR state while waiting for {target}")]
+    BackendError { target: BackendState },
+}
+
+impl WaitError {
+    /// Exit status for this error, so scripts can tell a timeout from a failed backend
+    #[must_use]
+    pub fn exit_status(&self) -> ExitStatus {
         match self {
-            Self::Started => write!(f, "STARTED"),
-            Self::Starting => write!(f, "STARTING"),
-            Self::Stopped => write!(f, "STOPPED"),
-            Self::Stopping => write!(f, "STOPPING"),
-            Self::Error => write!(f, "ERROR"),
-            Self::Disabled => write!(f, "DISABLED"),
-            Self::Unknown => write!(f, "UNKNOWN"),
+            Self::Timeout { .. } | Self::ConditionTimeout { .. } => ExitStatus::Timeout,
+            Self::BackendError { .. } => ExitStatus::BackendError,
         }
     }
 }
 
 impl BackendState {
-    /// Parse state from API response string
-    fn from_str(s: &str) -> Self {
-        match s.trim().trim_matches('"').to_uppercase().as_str() {
-            "STARTED" => Self::Started,
-            "STARTING" => Self::Starting,
-            "STOPPED" => Self::Stopped,
-            "STOPPING" => Self::Stopping,
-            "ERROR" => Self::Error,
-            "DISABLED" => Self::Disabled,
-            _ => Self::Unknown,
-        }
-    }
-
     /// Get colored status string for display
     fn colored(&self) -> colored::ColoredString {
         match self {
@@ -65,6 +66,17 @@ impl BackendState {
             Self::Unknown => "UNKNOWN".dimmed(),
         }
     }
+
+    /// Whether this state satisfies a wait for `target`.
+    ///
+    /// `DISABLED` means the backend is running with Kubernetes disabled,
+    /// so it counts as started.
+    pub(crate) fn satisfies(&self, target: &BackendState) -> bool {
+        match target {
+            Self::Started => matches!(self, Self::Started | Self::Disabled),
+            _ => self == target,
+        }
+    }
 }
 
 /// Status output structure
@@ -72,124 +84,670 @@ impl BackendState {
 pub struct StatusOutput {
     pub state: BackendState,
     pub api_endpoint: String,
+    /// When ranch-hand first saw the backend in its current state (RFC 3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_since: Option<String>,
+    /// Seconds since `state_since`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_age_secs: Option<u64>,
+    /// Kubernetes and cache details (only with `--details`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<StatusDetails>,
+}
+
+/// Settings and cache details for `rh status --details`
+#[derive(Debug, Serialize)]
+pub struct StatusDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kubernetes_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kubernetes_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_engine: Option<String>,
+    /// Cache state for the active Kubernetes version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub k3s_cache: Option<K3sCacheStatus>,
 }
 
-/// Start the Rancher Desktop backend
-pub async fn start(cli: &Cli) -> Result<()> {
+/// Whether the active Kubernetes version is in the local k3s cache
+#[derive(Debug, Serialize)]
+pub struct K3sCacheStatus {
+    /// Matching cache directory (e.g. `v1.33.3+k3s1`), if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// All expected files are present
+    pub cached: bool,
+    /// All files matched their published checksums
+    pub verified: bool,
+}
+
+/// Last backend state seen by ranch-hand, persisted between invocations
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedState {
+    state: String,
+    since: String,
+}
+
+/// File under the ranch-hand data dir that remembers the last observed state
+const STATE_FILE: &str = "backend-state.json";
+
+/// Start the Rancher Desktop backend, optionally waiting up to `wait` for STARTED
+pub async fn start(cli: &Cli, wait: Option<Duration>) -> Result<()> {
     info!("Starting Rancher Desktop backend");
-    set_backend_state(cli, "STARTED", "Starting").await
+    let client = connect(cli)?;
+    set_backend_state(cli, &client, &BackendState::Started, "Starting", wait).await
 }
 
-/// Stop the Rancher Desktop backend
-pub async fn stop(cli: &Cli) -> Result<()> {
+/// Stop the Rancher Desktop backend, optionally waiting up to `wait` for STOPPED
+pub async fn stop(cli: &Cli, wait: Option<Duration>) -> Result<()> {
     info!("Stopping Rancher Desktop backend");
-    set_backend_state(cli, "STOPPED", "Stopping").await
+    let client = connect(cli)?;
+    set_backend_state(cli, &client, &BackendState::Stopped, "Stopping", wait).await
 }
 
-/// Restart the Rancher Desktop backend
-pub async fn restart(cli: &Cli) -> Result<()> {
+/// Restart the Rancher Desktop backend.
+///
+/// Always waits for the backend to stop before starting it again; `wait`
+/// controls whether to also wait for it to be STARTED.
+pub async fn restart(cli: &Cli, wait: bool, wait_timeout: Duration) -> Result<()> {
     info!("Restarting Rancher Desktop backend");
+    let client = connect(cli)?;
+
+    set_backend_state(
+        cli,
+        &client,
+        &BackendState::Stopped,
+        "Stopping",
+        Some(wait_timeout),
+    )
+    .await?;
+
+    set_backend_state(
+        cli,
+        &client,
+        &BackendState::Started,
+        "Starting",
+        wait.then_some(wait_timeout),
+    )
+    .await
+}
 
-    // First stop, then start
-    set_backend_state(cli, "STOPPED", "Stopping").await?;
-
-    // Wait a moment for the backend to stop
-    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-
-    set_backend_state(cli, "STARTED", "Starting").await
+/// Restart the backend and wait for it to be STARTED, without printing its
+/// status, for commands that report the result in their own output
+pub(crate) async fn restart_silently(cli: &Cli, wait_timeout: Duration) -> Result<StatusOutput> {
+    let client = connect(cli)?;
+    transition(&client, &BackendState::Stopped, None, Some(wait_timeout)).await?;
+    transition(&client, &BackendState::Started, None, Some(wait_timeout)).await
 }
 
-/// Show the backend status
-pub async fn status(cli: &Cli) -> Result<()> {
+/// Show the backend status, with settings and cache details if `details`
+pub async fn status(cli: &Cli, details: bool) -> Result<()> {
     info!("Checking backend status");
 
-    let config = RdEngineConfig::load()
-        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+    let client = connect(cli)?;
+
+    let state = client.backend_state().await?;
+    let since = record_state(&state);
+    let age = since.map(|since| age_secs(since, Local::now()));
 
-    let state = get_backend_state(&config, cli).await?;
+    let details = if details {
+        Some(status_details(&client).await)
+    } else {
+        None
+    };
 
     if cli.json {
         let output = StatusOutput {
             state,
-            api_endpoint: config.api_base_url(),
+            api_endpoint: client.config().api_base_url(),
+            state_since: since.map(|s| s.to_rfc3339_opts(SecondsFormat::Secs, false)),
+            state_age_secs: age,
+            details,
         };
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
-        println!("Backend status: {}", state.colored());
+        match age {
+            Some(age) => println!(
+                "Backend status: {} {}",
+                state.colored(),
+                format!("(for {})", format_age(age)).dimmed()
+            ),
+            None => println!("Backend status: {}", state.colored()),
+        }
+        if let Some(details) = &details {
+            print_status_details(details);
+        }
     }
 
     Ok(())
 }
 
-/// Get the current backend state
-async fn get_backend_state(config: &RdEngineConfig, cli: &Cli) -> Result<BackendState> {
-    let client_config = HttpClientConfig::with_timeout(cli.insecure, cli.timeout);
-    let client = build_client(&client_config)?;
+async fn status_details(client: &RdClient) -> StatusDetails {
+    let info = match client.settings().await {
+        Ok(settings) => {
+            RancherDesktopInfo::from_settings(&settings, client.config().api_base_url())
+        }
+        Err(e) => {
+            debug!("Failed to fetch settings: {}", e);
+            return StatusDetails {
+                kubernetes_version: None,
+                kubernetes_enabled: None,
+                container_engine: None,
+                k3s_cache: None,
+            };
+        }
+    };
 
-    let url = config.api_url("/v1/backend_state");
-    debug!("Getting backend state from {}", url);
+    let k3s_cache = info.kubernetes_version.as_deref().map(k3s_cache_status);
 
-    let response = client
-        .get(&url)
-        .header("Authorization", config.basic_auth())
-        .send()
-        .await
-        .context("Failed to get backend state")?;
+    StatusDetails {
+        kubernetes_version: info.kubernetes_version,
+        kubernetes_enabled: info.kubernetes_enabled,
+        container_engine: info.container_engine,
+        k3s_cache,
+    }
+}
 
-    if !response.status().is_success() {
-        anyhow::bail!("Failed to get backend state: HTTP {}", response.status());
+/// Look up a Kubernetes version in the local k3s cache, verifying its checksums
+fn k3s_cache_status(kubernetes_version: &str) -> K3sCacheStatus {
+    let versions = k3s_cache_dir()
+        .map_err(anyhow::Error::from)
+        .and_then(|dir| {
+            if dir.exists() {
+                scan_cache_versions(&dir).map(|(versions, _)| versions)
+            } else {
+                Ok(Vec::new())
+            }
+        })
+        .unwrap_or_else(|e| {
+            debug!("Failed to scan k3s cache: {:#}", e);
+            Vec::new()
+        });
+
+    match find_cached_version(&versions, kubernetes_version) {
+        Some(cached) => K3sCacheStatus {
+            version: Some(cached.version.clone()),
+            cached: cached.complete,
+            verified: cached.verified(),
+        },
+        None => K3sCacheStatus {
+            version: None,
+            cached: false,
+            verified: false,
+        },
     }
+}
 
-    let body = response
-        .text()
-        .await
-        .context("Failed to read response body")?;
+fn print_status_details(details: &StatusDetails) {
+    let kubernetes = match (&details.kubernetes_version, details.kubernetes_enabled) {
+        (_, Some(false)) => "disabled".dimmed().to_string(),
+        (Some(version), _) => version.clone(),
+        (None, _) => "unknown".dimmed().to_string(),
+    };
+    println!("Kubernetes:     {kubernetes}");
 
-    Ok(BackendState::from_str(&body))
+    if let Some(engine) = &details.container_engine {
+        println!("Engine:         {engine}");
+    }
+
+    if let Some(cache) = &details.k3s_cache {
+        let line = match &cache.version {
+            Some(version) if cache.verified => {
+                format!("{} {}", version, "(verified)".green())
+            }
+            Some(version) if cache.cached => {
+                format!("{} {}", version, "(not verified)".yellow())
+            }
+            Some(version) => format!("{} {}", version, "(incomplete)".yellow()),
+            None => "not cached".red().to_string(),
+        };
+        println!("k3s cache:      {line}");
+    }
+}
+
+/// Remember `state` and return when ranch-hand first saw the backend in it.
+///
+/// Rancher Desktop doesn't report when its state last changed, so this is
+/// the earliest time any `rh status` saw the current state. Failures to read
+/// or write the state file are not fatal.
+fn record_state(state: &BackendState) -> Option<DateTime<Local>> {
+    let path = ranch_hand_data_dir().ok()?.join(STATE_FILE);
+    let now = Local::now();
+
+    let previous = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<RecordedState>(&content).ok());
+    if let Some(previous) = previous.filter(|p| p.state == state.to_string()) {
+        if let Ok(since) = DateTime::parse_from_rfc3339(&previous.since) {
+            return Some(since.with_timezone(&Local));
+        }
+    }
+
+    let record = RecordedState {
+        state: state.to_string(),
+        since: now.to_rfc3339_opts(SecondsFormat::Secs, false),
+    };
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(&path, serde_json::to_string(&record).unwrap_or_default()));
+    if let Err(e) = written {
+        debug!(
+            "Failed to record backend state in {}: {}",
+            path.display(),
+            e
+        );
+        return None;
+    }
+
+    Some(now)
+}
+
+fn age_secs(since: DateTime<Local>, now: DateTime<Local>) -> u64 {
+    u64::try_from((now - since).num_seconds()).unwrap_or(0)
+}
+
+/// Format a duration in seconds as a short human-readable age (e.g. "2h 13m")
+fn format_age(secs: u64) -> String {
+    let (days, hours, mins) = (secs / 86_400, (secs % 86_400) / 3600, (secs % 3600) / 60);
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {mins}m")
+    } else if mins > 0 {
+        format!("{mins}m {}s", secs % 60)
+    } else {
+        format!("{secs}s")
+    }
+}
+
+/// A backend state change observed by `rh status --watch`
+#[derive(Debug, Serialize)]
+pub struct StateTransition {
+    /// When the new state was first observed (RFC 3339)
+    pub timestamp: String,
+    /// New state, or `UNREACHABLE` if the API could not be reached
+    pub state: String,
+    /// State before the transition (absent for the first observation)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<String>,
+    /// Why the API was unreachable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
-/// Set the backend state via PUT request
-async fn set_backend_state(cli: &Cli, target_state: &str, action: &str) -> Result<()> {
-    let config = RdEngineConfig::load()
-        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+/// What a single poll saw
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Observed {
+    State(BackendState),
+    Unreachable,
+}
 
-    let client_config = HttpClientConfig::with_timeout(cli.insecure, cli.timeout);
-    let client = build_client(&client_config)?;
+impl std::fmt::Display for Observed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::State(state) => write!(f, "{state}"),
+            Self::Unreachable => write!(f, "UNREACHABLE"),
+        }
+    }
+}
+
+impl Observed {
+    fn colored(&self) -> colored::ColoredString {
+        match self {
+            Self::State(state) => state.colored(),
+            Self::Unreachable => "UNREACHABLE".red().dimmed(),
+        }
+    }
+}
 
-    let url = config.api_url("/v1/backend_state");
-    debug!("Setting backend state to {} via {}", target_state, url);
+/// Poll the backend state every `interval` and print each transition until interrupted.
+///
+/// An unreachable API is reported as its own state rather than an error, so
+/// Rancher Desktop starting up or crashing shows up as a transition.
+pub async fn watch(cli: &Cli, interval: Duration) -> Result<()> {
+    info!("Watching backend status every {}s", interval.as_secs());
 
     if !cli.quiet && !cli.json {
-        println!("{action} Rancher Desktop backend...");
+        println!("Watching backend status (Ctrl+C to stop)...");
+    }
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    let mut client = None;
+    let mut previous: Option<Observed> = None;
+
+    loop {
+        let (observed, error) = observe(cli, &mut client).await;
+
+        if previous.as_ref() != Some(&observed) {
+            if let Observed::State(state) = &observed {
+                record_state(state);
+            }
+            let now = Local::now();
+            let transition = StateTransition {
+                timestamp: now.to_rfc3339_opts(SecondsFormat::Millis, false),
+                state: observed.to_string(),
+                previous: previous.as_ref().map(ToString::to_string),
+                error,
+            };
+
+            if cli.json {
+                println!("{}", serde_json::to_string(&transition)?);
+            } else {
+                print_transition(
+                    &now,
+                    previous.as_ref(),
+                    &observed,
+                    transition.error.as_deref(),
+                );
+            }
+
+            previous = Some(observed);
+        }
+
+        tokio::select! {
+            () = tokio::time::sleep(interval) => {}
+            _ = &mut ctrl_c => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// Poll the backend state once, reloading rd-engine.json after failures
+/// since Rancher Desktop writes new credentials each time it starts.
+async fn observe(cli: &Cli, client: &mut Option<RdClient>) -> (Observed, Option<String>) {
+    if client.is_none() {
+        match RdClient::from_cli(cli) {
+            Ok(c) => *client = Some(c),
+            Err(e) => return (Observed::Unreachable, Some(e.to_string())),
+        }
     }
 
-    let response = client
-        .put(&url)
-        .header("Authorization", config.basic_auth())
-        .header("Content-Type", "application/json")
-        .body(format!("\"{target_state}\""))
-        .send()
-        .await
-        .context("Failed to set backend state")?;
+    let Some(rd) = client.as_ref() else {
+        return (Observed::Unreachable, None);
+    };
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("Failed to set backend state: HTTP {status} - {body}");
+    match rd.backend_state().await {
+        Ok(state) => (Observed::State(state), None),
+        Err(e) => {
+            debug!("Failed to get backend state: {}", e);
+            *client = None;
+            (Observed::Unreachable, Some(e.to_string()))
+        }
     }
+}
 
-    // Get the new state
-    let new_state = get_backend_state(&config, cli).await?;
+fn print_transition(
+    at: &DateTime<Local>,
+    previous: Option<&Observed>,
+    current: &Observed,
+    error: Option<&str>,
+) {
+    let timestamp = at.format("%Y-%m-%d %H:%M:%S").to_string();
+    let line = match previous {
+        Some(previous) => format!("{} -> {}", previous.colored(), current.colored()),
+        None => current.colored().to_string(),
+    };
+
+    match error {
+        // Only the first line of errors like ConfigError::NotFound is useful here
+        Some(error) => println!(
+            "{}  {}  {}",
+            timestamp.dimmed(),
+            line,
+            error.lines().next().unwrap_or_default().dimmed()
+        ),
+        None => println!("{}  {}", timestamp.dimmed(), line),
+    }
+}
+
+/// Poll the backend state until it reaches `target`, fails, or `timeout` elapses.
+///
+/// Connection errors while polling are treated as transient and retried. An
+/// ERROR state only fails the wait once the backend has left ERROR, so a
+/// backend can be started or restarted out of a previous failure.
+pub async fn wait_for_state(
+    client: &RdClient,
+    target: &BackendState,
+    timeout: Duration,
+    show_progress: bool,
+) -> Result<BackendState> {
+    let spinner = show_progress.then(|| {
+        let sp = ProgressBar::new_spinner();
+        sp.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.cyan} {msg} [{elapsed}]")
+                .expect("valid spinner template"),
+        );
+        sp.set_message(format!("Waiting for backend to reach {target}..."));
+        sp.enable_steady_tick(Duration::from_millis(100));
+        sp
+    });
+
+    let result = poll_state(client, target, timeout, spinner.as_ref()).await;
+
+    if let Some(sp) = spinner {
+        sp.finish_and_clear();
+    }
+
+    result
+}
+
+/// Decides whether an ERROR state seen while waiting is a new failure.
+///
+/// Right after a state change is requested the backend may still report the
+/// ERROR it is recovering from, so ERROR only counts after another state.
+#[derive(Debug, Default)]
+struct ErrorWatch {
+    left_error: bool,
+}
+
+impl ErrorWatch {
+    fn failed(&mut self, state: &BackendState) -> bool {
+        if *state == BackendState::Error {
+            self.left_error
+        } else {
+            self.left_error = true;
+            false
+        }
+    }
+}
+
+async fn poll_state(
+    client: &RdClient,
+    target: &BackendState,
+    timeout: Duration,
+    spinner: Option<&ProgressBar>,
+) -> Result<BackendState> {
+    let started = Instant::now();
+    let mut last_state = BackendState::Unknown;
+    let mut errors = ErrorWatch::default();
+
+    loop {
+        match client.backend_state().await {
+            Ok(state) => {
+                debug!("Backend state while waiting for {}: {}", target, state);
+                if state.satisfies(target) {
+                    return Ok(state);
+                }
+                if errors.failed(&state) {
+                    return Err(WaitError::BackendError {
+                        target: target.clone(),
+                    }
+                    .into());
+                }
+                if let Some(sp) = spinner {
+                    sp.set_message(format!(
+                        "Waiting for backend to reach {target} (currently {state})..."
+                    ));
+                }
+                last_state = state;
+            }
+            Err(RdClientError::Http(e)) => {
+                debug!("Transient error while polling backend state: {}", e);
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            return Err(WaitError::Timeout {
+                target: target.clone(),
+                last_state,
+                elapsed,
+            }
+            .into());
+        }
+
+        tokio::time::sleep(WAIT_POLL_INTERVAL.min(timeout - elapsed)).await;
+    }
+}
+
+fn connect(cli: &Cli) -> Result<RdClient> {
+    RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")
+}
+
+/// Set the backend state via PUT request, optionally waiting for the transition
+async fn set_backend_state(
+    cli: &Cli,
+    client: &RdClient,
+    target_state: &BackendState,
+    action: &str,
+    wait: Option<Duration>,
+) -> Result<()> {
+    let show_progress = !cli.quiet && !cli.json;
+    let output = transition(client, target_state, show_progress.then_some(action), wait).await?;
 
     if cli.json {
-        let output = StatusOutput {
-            state: new_state,
-            api_endpoint: config.api_base_url(),
-        };
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else if !cli.quiet {
-        println!("Backend status: {}", new_state.colored());
+        println!("Backend status: {}", output.state.colored());
     }
 
     Ok(())
 }
+
+/// Request `target_state` and return the resulting status, announcing the
+/// `action` and showing wait progress when one is given
+async fn transition(
+    client: &RdClient,
+    target_state: &BackendState,
+    action: Option<&str>,
+    wait: Option<Duration>,
+) -> Result<StatusOutput> {
+    if let Some(action) = action {
+        println!("{action} Rancher Desktop backend...");
+    }
+
+    client.set_backend_state(target_state).await?;
+
+    // Get the new state, waiting for the transition if requested
+    let new_state = match wait {
+        Some(timeout) => wait_for_state(client, target_state, timeout, action.is_some()).await?,
+        None => client.backend_state().await?,
+    };
+    let since = record_state(&new_state);
+
+    Ok(StatusOutput {
+        state: new_state,
+        api_endpoint: client.config().api_base_url(),
+        state_since: since.map(|s| s.to_rfc3339_opts(SecondsFormat::Secs, false)),
+        state_age_secs: since.map(|since| age_secs(since, Local::now())),
+        details: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_satisfies_target() {
+        assert!(BackendState::Started.satisfies(&BackendState::Started));
+        assert!(BackendState::Disabled.satisfies(&BackendState::Started));
+        assert!(!BackendState::Starting.satisfies(&BackendState::Started));
+        assert!(BackendState::Stopped.satisfies(&BackendState::Stopped));
+        assert!(!BackendState::Disabled.satisfies(&BackendState::Stopped));
+    }
+
+    #[test]
+    fn test_error_watch_allows_recovery_from_error() {
+        use BackendState::{Error, Starting, Stopped, Stopping};
+
+        // Restarting out of ERROR: the first polls still report ERROR
+        let mut watch = ErrorWatch::default();
+        let failures: Vec<bool> = [Error, Error, Stopping, Stopped]
+            .iter()
+            .map(|state| watch.failed(state))
+            .collect();
+        assert_eq!(failures, [false, false, false, false]);
+
+        // Falling into ERROR after leaving it is a failure
+        let mut watch = ErrorWatch::default();
+        assert!(!watch.failed(&Error));
+        assert!(!watch.failed(&Starting));
+        assert!(watch.failed(&Error));
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(42), "42s");
+        assert_eq!(format_age(192), "3m 12s");
+        assert_eq!(format_age(2 * 3600 + 13 * 60 + 5), "2h 13m");
+        assert_eq!(format_age(3 * 86_400 + 4 * 3600), "3d 4h");
+    }
+
+    #[test]
+    fn test_age_secs_never_negative() {
+        let now = Local::now();
+        assert_eq!(age_secs(now + chrono::Duration::seconds(10), now), 0);
+        assert_eq!(age_secs(now - chrono::Duration::seconds(90), now), 90);
+    }
+
+    #[test]
+    fn test_state_transition_serialization() {
+        let transition = StateTransition {
+            timestamp: "2025-01-01T12:00:00.000+00:00".to_string(),
+            state: Observed::Unreachable.to_string(),
+            previous: Some(Observed::State(BackendState::Started).to_string()),
+            error: Some("Connection refused".to_string()),
+        };
+        assert_eq!(
+            serde_json::to_string(&transition).unwrap(),
+            r#"{"timestamp":"2025-01-01T12:00:00.000+00:00","state":"UNREACHABLE","previous":"STARTED","error":"Connection refused"}"#
+        );
+
+        let first = StateTransition {
+            timestamp: "2025-01-01T12:00:00.000+00:00".to_string(),
+            state: "STARTING".to_string(),
+            previous: None,
+            error: None,
+        };
+        let json = serde_json::to_string(&first).unwrap();
+        assert!(!json.contains("previous"));
+        assert!(!json.contains("error"));
+    }
+
+    #[test]
+    fn test_wait_error_exit_codes() {
+        let timeout = WaitError::Timeout {
+            target: BackendState::Started,
+            last_state: BackendState::Starting,
+            elapsed: Duration::from_secs(300),
+        };
+        assert_eq!(timeout.exit_status(), ExitStatus::Timeout);
+        assert_eq!(
+            timeout.to_string(),
+            "Timed out after 300s waiting for backend to reach STARTED (last state: STARTING)"
+        );
+
+        let error = WaitError::BackendError {
+            target: BackendState::Started,
+        };
+        assert_eq!(error.exit_status(), ExitStatus::BackendError);
+    }
+}
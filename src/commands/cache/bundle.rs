@@ -0,0 +1,494 @@
+//! Offline cache bundles for seeding air-gapped machines.
+//!
+//! A bundle is an uncompressed tar archive (the airgap images are already
+//! compressed) holding a `manifest.json` followed by the files of one cached
+//! k3s version under `<version>/`. The manifest records each file's size and
+//! SHA256. On import every file is verified against the manifest, and then
+//! against the bundled `sha256sum-<arch>.txt` the same way `rh cache verify`
+//! does, before it is moved into the cache.
+
+use super::verify::{check_version, FileStatus};
+use crate::releases::is_release_name;
+use crate::utils::checksum::{calculate_file_hash, verify_file};
+use crate::utils::download::is_partial_download;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::path::{Component, Path, PathBuf};
+
+/// Name of the manifest entry, which must come first in the archive
+pub const MANIFEST_NAME: &str = "manifest.json";
+
+/// Bundle format written by this version of ranch-hand
+const FORMAT_VERSION: u32 = 1;
+
+/// Describes the contents of a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    /// k3s release, e.g. `v1.33.3+k3s1`
+    pub version: String,
+    /// Architecture the files are for (`amd64` or `arm64`)
+    pub arch: String,
+    pub created: String,
+    pub files: Vec<BundleFile>,
+}
+
+/// A file in a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleFile {
+    pub name: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+impl BundleManifest {
+    /// Total size of the bundled files in bytes
+    #[must_use]
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+}
+
+/// Default bundle file name for a version
+#[must_use]
+pub fn default_bundle_name(version: &str, arch: &str) -> String {
+    format!("k3s-{version}-{arch}.tar")
+}
+
+/// Write the files in `version_dir` to a bundle at `output`.
+///
+/// Interrupted download leftovers (`.part` files) are not bundled.
+pub fn export_bundle(
+    version_dir: &Path,
+    version: &str,
+    arch: &str,
+    output: &Path,
+    now: DateTime<Local>,
+) -> Result<BundleManifest> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(version_dir)
+        .with_context(|| format!("Failed to read {}", version_dir.display()))?
+    {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if entry.file_type()?.is_file() && !name.starts_with('.') && !is_partial_download(&name) {
+            names.push(name);
+        }
+    }
+    names.sort();
+
+    let mut files = Vec::new();
+    for name in names {
+        let path = version_dir.join(&name);
+        files.push(BundleFile {
+            size: fs::metadata(&path)?.len(),
+            sha256: calculate_file_hash(&path)?,
+            name,
+        });
+    }
+
+    let manifest = BundleManifest {
+        format_version: FORMAT_VERSION,
+        version: version.to_string(),
+        arch: arch.to_string(),
+        created: now.to_rfc3339(),
+        files,
+    };
+
+    let result = write_bundle(version_dir, &manifest, output);
+    if result.is_err() {
+        let _ = fs::remove_file(output);
+    }
+    result.with_context(|| format!("Failed to write bundle {}", output.display()))?;
+
+    Ok(manifest)
+}
+
+fn write_bundle(version_dir: &Path, manifest: &BundleManifest, output: &Path) -> Result<()> {
+    let file = File::create(output)?;
+    let mut builder = tar::Builder::new(file);
+
+    let manifest_json = serde_json::to_vec_pretty(manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(
+        DateTime::parse_from_rfc3339(&manifest.created)
+            .map(|t| t.timestamp().unsigned_abs())
+            .unwrap_or_default(),
+    );
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_NAME, manifest_json.as_slice())?;
+
+    for file in &manifest.files {
+        builder.append_path_with_name(
+            version_dir.join(&file.name),
+            format!("{}/{}", manifest.version, file.name),
+        )?;
+    }
+
+    builder.into_inner()?.sync_all()?;
+    Ok(())
+}
+
+/// Unpack the bundle at `bundle` into `cache_dir/<version>`.
+///
+/// Files are extracted to a hidden staging directory and verified against the
+/// manifest and the release checksums before replacing the cached version, so
+/// a corrupt bundle never leaves a half-written version behind. An existing
+/// cached version is only replaced when `force` is set, and is kept until the
+/// new one is in place.
+pub fn import_bundle(
+    bundle: &Path,
+    cache_dir: &Path,
+    arch: &str,
+    force: bool,
+) -> Result<(BundleManifest, PathBuf)> {
+    let file =
+        File::open(bundle).with_context(|| format!("Failed to open {}", bundle.display()))?;
+    let mut archive = tar::Archive::new(file);
+    let mut entries = archive
+        .entries()
+        .with_context(|| format!("Failed to read {}", bundle.display()))?;
+
+    let manifest = read_manifest(entries.next())
+        .with_context(|| format!("{} is not a ranch-hand cache bundle", bundle.display()))?;
+
+    if manifest.arch != arch {
+        anyhow::bail!(
+            "Bundle is for {} but this machine needs {arch}. Export the bundle again with an {arch} cache.",
+            manifest.arch
+        );
+    }
+
+    let target = cache_dir.join(&manifest.version);
+    if target.exists() && !force {
+        anyhow::bail!(
+            "k3s {} is already cached at {}. Use --force to replace it.",
+            manifest.version,
+            target.display()
+        );
+    }
+
+    let staging = cache_dir.join(format!(".import-{}", manifest.version));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)
+            .with_context(|| format!("Failed to remove {}", staging.display()))?;
+    }
+    fs::create_dir_all(&staging)
+        .with_context(|| format!("Failed to create {}", staging.display()))?;
+
+    let result = unpack_files(entries, &manifest, &staging)
+        .and_then(|()| verify_release_checksums(&staging, &manifest))
+        .and_then(|()| replace_version(&staging, &target));
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&staging);
+    }
+    result?;
+
+    Ok((manifest, target))
+}
+
+/// Check the unpacked files against the bundled release checksums, as export
+/// requires of the cache it bundles
+fn verify_release_checksums(staging: &Path, manifest: &BundleManifest) -> Result<()> {
+    let check = check_version(staging, &manifest.version, &manifest.arch);
+    if check.is_verified() {
+        return Ok(());
+    }
+
+    let problems: Vec<String> = check
+        .files
+        .iter()
+        .filter(|f| f.status != FileStatus::Ok)
+        .map(|f| format!("{} ({})", f.name, f.status))
+        .collect();
+    anyhow::bail!(
+        "Bundle does not match sha256sum-{}.txt: {}",
+        manifest.arch,
+        problems.join(", ")
+    )
+}
+
+/// Move `staging` to `target`, moving an existing `target` aside first and
+/// restoring it if the move fails
+fn replace_version(staging: &Path, target: &Path) -> Result<()> {
+    let Some(name) = target.file_name().and_then(|n| n.to_str()) else {
+        anyhow::bail!("Invalid cache path {}", target.display());
+    };
+    let previous = target.with_file_name(format!(".replaced-{name}"));
+
+    if previous.exists() {
+        fs::remove_dir_all(&previous)
+            .with_context(|| format!("Failed to remove {}", previous.display()))?;
+    }
+    let replacing = target.exists();
+    if replacing {
+        fs::rename(target, &previous)
+            .with_context(|| format!("Failed to move {} aside", target.display()))?;
+    }
+
+    if let Err(e) = fs::rename(staging, target) {
+        if replacing {
+            let _ = fs::rename(&previous, target);
+        }
+        return Err(e).with_context(|| format!("Failed to move import into {}", target.display()));
+    }
+
+    if replacing {
+        let _ = fs::remove_dir_all(&previous);
+    }
+    Ok(())
+}
+
+type Entries<'a> = tar::Entries<'a, File>;
+type Entry<'a> = tar::Entry<'a, File>;
+
+fn read_manifest(entry: Option<std::io::Result<Entry<'_>>>) -> Result<BundleManifest> {
+    let mut entry = entry.context("Archive is empty")??;
+    if entry.path()?.as_ref() != Path::new(MANIFEST_NAME) {
+        anyhow::bail!("Archive does not start with {MANIFEST_NAME}");
+    }
+
+    let manifest: BundleManifest =
+        serde_json::from_reader(&mut entry).context("Failed to parse bundle manifest")?;
+    if manifest.format_version > FORMAT_VERSION {
+        anyhow::bail!(
+            "Bundle format {} is newer than this version of rh supports ({FORMAT_VERSION}); upgrade rh to import it",
+            manifest.format_version
+        );
+    }
+
+    if !is_release_name(&manifest.version) {
+        anyhow::bail!("Invalid k3s version in manifest: {:?}", manifest.version);
+    }
+    if manifest.files.is_empty() {
+        anyhow::bail!("Manifest lists no files");
+    }
+    for file in &manifest.files {
+        if !is_plain_file_name(&file.name) {
+            anyhow::bail!("Invalid file name in manifest: {}", file.name);
+        }
+    }
+
+    Ok(manifest)
+}
+
+fn unpack_files(entries: Entries<'_>, manifest: &BundleManifest, staging: &Path) -> Result<()> {
+    for entry in entries {
+        let mut entry = entry?;
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
+
+        let path = entry.path()?.into_owned();
+        let name = bundled_file_name(&path, &manifest.version)
+            .filter(|name| manifest.files.iter().any(|f| f.name == *name))
+            .with_context(|| format!("Unexpected file in bundle: {}", path.display()))?;
+        if !entry.header().entry_type().is_file() {
+            anyhow::bail!("Unexpected file in bundle: {}", path.display());
+        }
+
+        entry
+            .unpack(staging.join(&name))
+            .with_context(|| format!("Failed to extract {name}"))?;
+    }
+
+    for file in &manifest.files {
+        let path = staging.join(&file.name);
+        if !path.is_file() {
+            anyhow::bail!("Bundle is missing {}", file.name);
+        }
+        verify_file(&path, &file.sha256)?;
+    }
+
+    Ok(())
+}
+
+/// The file name of `path` if it is `<version>/<name>`
+fn bundled_file_name(path: &Path, version: &str) -> Option<String> {
+    let mut components = path.components();
+    match (components.next(), components.next(), components.next()) {
+        (Some(Component::Normal(dir)), Some(Component::Normal(name)), None)
+            if dir.to_str() == Some(version) =>
+        {
+            name.to_str().map(ToString::to_string)
+        }
+        _ => None,
+    }
+}
+
+fn is_plain_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && Path::new(name).file_name().and_then(|n| n.to_str()) == Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const VERSION: &str = "v1.33.3+k3s1";
+
+    fn seed_version(cache_dir: &Path) -> PathBuf {
+        let dir = cache_dir.join(VERSION);
+        fs::create_dir_all(&dir).unwrap();
+        let binary = crate::paths::k3s_binary_name();
+        fs::write(dir.join(binary), b"k3s binary").unwrap();
+        fs::write(dir.join("k3s-airgap-images-amd64.tar.gz"), b"images").unwrap();
+        let sums: Vec<String> = [binary, "k3s-airgap-images-amd64.tar.gz"]
+            .iter()
+            .map(|name| {
+                format!(
+                    "{}  {name}\n",
+                    calculate_file_hash(&dir.join(name)).unwrap()
+                )
+            })
+            .collect();
+        fs::write(dir.join("sha256sum-amd64.txt"), sums.concat()).unwrap();
+        fs::write(dir.join("k3s-airgap-images-amd64.tar.zst.part"), b"partial").unwrap();
+        fs::write(dir.join("k3s-airgap-images-amd64.tar.zst.part.json"), b"{}").unwrap();
+        dir
+    }
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 16, 9, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn test_export_and_import() {
+        let source = tempfile::tempdir().unwrap();
+        let target = tempfile::tempdir().unwrap();
+        let version_dir = seed_version(source.path());
+        let bundle = source.path().join(default_bundle_name(VERSION, "amd64"));
+
+        let manifest = export_bundle(&version_dir, VERSION, "amd64", &bundle, now()).unwrap();
+        let names: Vec<&str> = manifest.files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                crate::paths::k3s_binary_name(),
+                "k3s-airgap-images-amd64.tar.gz",
+                "sha256sum-amd64.txt"
+            ]
+        );
+
+        let (imported, path) = import_bundle(&bundle, target.path(), "amd64", false).unwrap();
+        assert_eq!(imported.version, VERSION);
+        assert_eq!(path, target.path().join(VERSION));
+        assert_eq!(
+            fs::read(path.join(crate::paths::k3s_binary_name())).unwrap(),
+            b"k3s binary"
+        );
+        assert!(!path.join("k3s-airgap-images-amd64.tar.zst.part").exists());
+
+        // Importing again needs --force, and leaves nothing but the version
+        let err = import_bundle(&bundle, target.path(), "amd64", false).unwrap_err();
+        assert!(err.to_string().contains("--force"), "{err}");
+        assert!(import_bundle(&bundle, target.path(), "amd64", true).is_ok());
+        assert_eq!(fs::read_dir(target.path()).unwrap().count(), 1);
+
+        let err = import_bundle(&bundle, target.path(), "arm64", true).unwrap_err();
+        assert!(err.to_string().contains("amd64"), "{err}");
+    }
+
+    #[test]
+    fn test_import_rejects_corrupt_bundle() {
+        let source = tempfile::tempdir().unwrap();
+        let target = tempfile::tempdir().unwrap();
+        let version_dir = seed_version(source.path());
+        let bundle = source.path().join("bundle.tar");
+
+        let mut manifest = export_bundle(&version_dir, VERSION, "amd64", &bundle, now()).unwrap();
+        manifest.files[0].sha256 = "0".repeat(64);
+        write_bundle(&version_dir, &manifest, &bundle).unwrap();
+
+        let err = import_bundle(&bundle, target.path(), "amd64", false).unwrap_err();
+        assert!(format!("{err:#}").contains("Checksum mismatch"), "{err:#}");
+        // Nothing is left behind, not even the staging directory
+        assert_eq!(fs::read_dir(target.path()).unwrap().count(), 0);
+
+        let not_a_bundle = source.path().join("other.tar");
+        let mut builder = tar::Builder::new(File::create(&not_a_bundle).unwrap());
+        builder
+            .append_path_with_name(version_dir.join("sha256sum-amd64.txt"), "k3s")
+            .unwrap();
+        builder.into_inner().unwrap();
+        let err = import_bundle(&not_a_bundle, target.path(), "amd64", false).unwrap_err();
+        assert!(
+            err.to_string().contains("not a ranch-hand cache bundle"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_import_checks_release_checksums() {
+        let source = tempfile::tempdir().unwrap();
+        let target = tempfile::tempdir().unwrap();
+        let version_dir = seed_version(source.path());
+        let bundle = source.path().join("bundle.tar");
+        let mut manifest = export_bundle(&version_dir, VERSION, "amd64", &bundle, now()).unwrap();
+
+        // A bundle holding only the checksums file is not a usable cache
+        manifest.files.retain(|f| f.name == "sha256sum-amd64.txt");
+        write_bundle(&version_dir, &manifest, &bundle).unwrap();
+        let err = import_bundle(&bundle, target.path(), "amd64", false).unwrap_err();
+        assert!(format!("{err:#}").contains("missing"), "{err:#}");
+
+        // A file that matches the manifest but not the release checksums
+        fs::write(
+            version_dir.join(crate::paths::k3s_binary_name()),
+            b"tampered",
+        )
+        .unwrap();
+        export_bundle(&version_dir, VERSION, "amd64", &bundle, now()).unwrap();
+        let err = import_bundle(&bundle, target.path(), "amd64", false).unwrap_err();
+        assert!(format!("{err:#}").contains("corrupt"), "{err:#}");
+        assert_eq!(fs::read_dir(target.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_manifest_rejects_unsafe_versions() {
+        let source = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        let version_dir = seed_version(source.path());
+        let bundle = source.path().join("bundle.tar");
+        let mut manifest = export_bundle(&version_dir, VERSION, "amd64", &bundle, now()).unwrap();
+
+        // An existing cached version must survive a hostile manifest
+        let cached = seed_version(cache.path());
+        for version in [".", ".import-v1.33.3+k3s1", "v1.33.3"] {
+            manifest.version = version.to_string();
+            write_bundle(&version_dir, &manifest, &bundle).unwrap();
+            let err = import_bundle(&bundle, cache.path(), "amd64", true).unwrap_err();
+            assert!(
+                format!("{err:#}").contains("Invalid k3s version"),
+                "{version}: {err:#}"
+            );
+        }
+
+        manifest.version = VERSION.to_string();
+        manifest.files.clear();
+        write_bundle(&version_dir, &manifest, &bundle).unwrap();
+        let err = import_bundle(&bundle, cache.path(), "amd64", true).unwrap_err();
+        assert!(format!("{err:#}").contains("no files"), "{err:#}");
+
+        assert!(cached.join("sha256sum-amd64.txt").is_file());
+    }
+
+    #[test]
+    fn test_bundled_file_name() {
+        assert_eq!(
+            bundled_file_name(Path::new("v1.33.3+k3s1/k3s"), VERSION).as_deref(),
+            Some("k3s")
+        );
+        assert!(bundled_file_name(Path::new("v1.32.7+k3s1/k3s"), VERSION).is_none());
+        assert!(bundled_file_name(Path::new("v1.33.3+k3s1/../k3s"), VERSION).is_none());
+        assert!(bundled_file_name(Path::new("/etc/passwd"), VERSION).is_none());
+        assert!(!is_plain_file_name("../k3s"));
+        assert!(is_plain_file_name("k3s"));
+    }
+}
@@ -1,40 +1,34 @@
 //! Cache management commands for k3s files.
 
+pub mod bundle;
+pub mod verify;
+
 use crate::cli::Cli;
 use crate::client::http::{build_client, HttpClientConfig};
+use crate::exit::CheckError;
 use crate::paths::{arch_string, k3s_binary_name, k3s_cache_dir, k3s_version_cache_dir};
+use crate::releases::K3sReleases;
 use crate::utils::checksum::{parse_checksum_file, verify_file_from_checksums, ChecksumError};
 use crate::utils::download::{
-    check_existing_file, cleanup_partial_download, stream_to_file, DownloadManager,
+    check_existing_file, cleanup_partial_download, content_range_start, discard_partial_download,
+    finish_partial_download, is_retryable, part_path, resumable_download, save_partial_download,
+    stream_to_file, DownloadError, DownloadManager, PartialDownload, RetryPolicy,
 };
 use anyhow::{anyhow, Context, Result};
+use bundle::BundleManifest;
 use colored::Colorize;
 use dialoguer::FuzzySelect;
 use futures_util::future::join_all;
 use indicatif::{ProgressBar, ProgressStyle};
-use serde::{Deserialize, Serialize};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::fs;
 use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
-
-/// k3s release base URL
-const K3S_RELEASES_URL: &str = "https://github.com/k3s-io/k3s/releases/download";
-
-/// k3s releases API URL
-const K3S_RELEASES_API_URL: &str = "https://api.github.com/repos/k3s-io/k3s/releases";
-
-/// Maximum number of versions to fetch from GitHub API
-const MAX_VERSIONS_TO_FETCH: usize = 50;
-
-/// GitHub release response structure
-#[derive(Debug, Deserialize)]
-struct GitHubRelease {
-    tag_name: String,
-    prerelease: bool,
-    draft: bool,
-}
+use verify::{FileStatus, VersionCheck};
 
 /// Files to download for cache populate
 fn get_download_files(arch: &str) -> Vec<(&'static str, String)> {
@@ -45,6 +39,15 @@ fn get_download_files(arch: &str) -> Vec<(&'static str, String)> {
     ]
 }
 
+/// Airgap image file names, in the order releases are tried
+fn image_file_names(arch: &str) -> [String; 3] {
+    [
+        format!("k3s-airgap-images-{arch}.tar.zst"),
+        format!("k3s-airgap-images-{arch}.tar.gz"),
+        format!("k3s-airgap-images-{arch}.tar"),
+    ]
+}
+
 /// Represents a cached k3s file with its verification status.
 #[derive(Debug, Clone, Serialize)]
 pub struct CachedFile {
@@ -125,7 +128,10 @@ fn print_empty_cache(cli: &Cli, cache_dir: &Path) -> Result<()> {
     Ok(())
 }
 
-fn scan_cache_versions(cache_dir: &Path) -> Result<(Vec<CachedVersion>, u64)> {
+/// Scan a k3s cache directory and return its versions, newest first, with their total size.
+///
+/// Each file is verified against the version's `sha256sum-<arch>.txt` when present.
+pub fn scan_cache_versions(cache_dir: &Path) -> Result<(Vec<CachedVersion>, u64)> {
     let mut versions = Vec::new();
     let mut total_size: u64 = 0;
 
@@ -168,6 +174,44 @@ fn scan_cache_versions(cache_dir: &Path) -> Result<(Vec<CachedVersion>, u64)> {
     Ok((versions, total_size))
 }
 
+/// Find the newest cached k3s build for a Kubernetes version from settings.
+///
+/// Settings hold versions like `1.33.3`, while cache directories are named
+/// after k3s releases like `v1.33.3+k3s1`.
+#[must_use]
+pub fn find_cached_version<'a>(
+    versions: &'a [CachedVersion],
+    kubernetes_version: &str,
+) -> Option<&'a CachedVersion> {
+    let wanted = kubernetes_version.trim().trim_start_matches('v');
+    versions.iter().find(|v| {
+        v.version
+            .trim_start_matches('v')
+            .split('+')
+            .next()
+            .is_some_and(|base| base == wanted)
+    })
+}
+
+impl CachedVersion {
+    /// Whether every expected file is present and matched its checksum
+    #[must_use]
+    pub fn verified(&self) -> bool {
+        // The checksums file has nothing to be checked against
+        let expected = get_download_files(arch_string());
+        self.complete
+            && self
+                .files
+                .iter()
+                .filter(|f| {
+                    expected
+                        .iter()
+                        .any(|(file_type, name)| *file_type != "checksums" && *name == f.name)
+                })
+                .all(|f| f.verified == Some(true))
+    }
+}
+
 fn scan_version_files(path: &Path) -> Result<(Vec<CachedFile>, u64, bool)> {
     let mut files = Vec::new();
     let mut total_size: u64 = 0;
@@ -334,9 +378,9 @@ fn validate_version(version: &str) -> Result<()> {
     Ok(())
 }
 
-/// Fetch available k3s versions from GitHub API
-async fn fetch_available_versions(cli: &Cli) -> Result<Vec<String>> {
-    let url = format!("{K3S_RELEASES_API_URL}?per_page={MAX_VERSIONS_TO_FETCH}");
+/// Fetch available k3s versions from GitHub or the configured mirror
+async fn fetch_available_versions(cli: &Cli, releases: &K3sReleases) -> Result<Vec<String>> {
+    let url = releases.list_url();
     debug!("Fetching k3s releases from: {}", url);
 
     let client = build_client(&HttpClientConfig::new(cli.insecure))?;
@@ -346,7 +390,7 @@ async fn fetch_available_versions(cli: &Cli) -> Result<Vec<String>> {
         .header("User-Agent", "ranch-hand")
         .send()
         .await
-        .context("Failed to fetch k3s releases from GitHub")?;
+        .with_context(|| format!("Failed to fetch k3s releases from {releases}"))?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -356,23 +400,17 @@ async fn fetch_available_versions(cli: &Cli) -> Result<Vec<String>> {
         } else {
             body
         };
-        return Err(anyhow!("GitHub API returned status {status}: {details}"));
+        return Err(anyhow!(
+            "{url} returned status {status}: {details}\n\
+             Specify a version to skip listing releases: rh cache populate <version>"
+        ));
     }
 
-    let releases: Vec<GitHubRelease> = response
-        .json()
+    let body = response
+        .text()
         .await
-        .context("Failed to parse GitHub releases response")?;
-
-    let versions: Vec<String> = releases
-        .into_iter()
-        .filter(|r| !r.prerelease && !r.draft)
-        .map(|r| r.tag_name)
-        .collect();
-
-    if versions.is_empty() {
-        return Err(anyhow!("No stable k3s releases found"));
-    }
+        .context("Failed to read k3s releases response")?;
+    let versions = releases.parse_versions(&body)?;
 
     debug!("Found {} stable k3s versions", versions.len());
     Ok(versions)
@@ -401,27 +439,24 @@ fn select_version_interactive(versions: &[String]) -> Result<String> {
     Ok(versions[selection].clone())
 }
 
-/// Populate cache with k3s files for a specific version
-pub async fn populate(cli: &Cli, version: Option<&str>, force: bool) -> Result<()> {
+/// Populate cache with k3s files for a specific version.
+///
+/// Failed downloads are retried according to `retry`.
+pub async fn populate(
+    cli: &Cli,
+    version: Option<&str>,
+    force: bool,
+    retry: RetryPolicy,
+) -> Result<()> {
+    let releases = K3sReleases::from_cli(cli)?;
+
     // If no version provided, fetch available versions and let user select
     let version = if let Some(v) = version {
         v.to_string()
     } else {
-        let spinner = if cli.quiet {
-            None
-        } else {
-            let sp = ProgressBar::new_spinner();
-            sp.set_style(
-                ProgressStyle::default_spinner()
-                    .template("{spinner:.cyan} {msg}")
-                    .expect("valid spinner template"),
-            );
-            sp.set_message("Fetching available k3s versions...");
-            sp.enable_steady_tick(std::time::Duration::from_millis(100));
-            Some(sp)
-        };
+        let spinner = spinner(!cli.quiet, "Fetching available k3s versions...");
 
-        let versions = fetch_available_versions(cli).await;
+        let versions = fetch_available_versions(cli, &releases).await;
 
         if let Some(sp) = spinner {
             sp.finish_and_clear();
@@ -439,7 +474,7 @@ pub async fn populate(cli: &Cli, version: Option<&str>, force: bool) -> Result<(
     info!("Populating cache for k3s {version} ({arch})");
     debug!("Cache directory: {}", version_dir.display());
 
-    print_populate_header(cli, &version, arch, &version_dir, force);
+    print_populate_header(cli, &version, arch, &version_dir, &releases, force);
 
     fs::create_dir_all(&version_dir).with_context(|| {
         format!(
@@ -448,20 +483,40 @@ pub async fn populate(cli: &Cli, version: Option<&str>, force: bool) -> Result<(
         )
     })?;
 
-    let checksums = download_checksums(cli, &version, arch, &version_dir).await?;
-    download_remaining_files(cli, &version, arch, &version_dir, &checksums, force).await?;
+    let checksums = download_checksums(cli, &releases, &version, arch, &version_dir, retry).await?;
+    download_remaining_files(
+        cli,
+        &releases,
+        &version,
+        arch,
+        &version_dir,
+        &checksums,
+        force,
+        retry,
+    )
+    .await?;
     verify_and_print_success(cli, &version_dir, &checksums, force)?;
 
     Ok(())
 }
 
-fn print_populate_header(cli: &Cli, version: &str, arch: &str, version_dir: &Path, force: bool) {
+fn print_populate_header(
+    cli: &Cli,
+    version: &str,
+    arch: &str,
+    version_dir: &Path,
+    releases: &K3sReleases,
+    force: bool,
+) {
     if !cli.quiet {
         println!("{}", "Rancher Desktop K3s Cache Setup".bold().cyan());
         println!();
         println!("Version: {}", version.yellow());
         println!("Architecture: {arch}");
         println!("Cache directory: {}", version_dir.display());
+        if releases.is_mirror() {
+            println!("Mirror: {releases}");
+        }
         if force {
             println!(
                 "{} {}",
@@ -475,12 +530,14 @@ fn print_populate_header(cli: &Cli, version: &str, arch: &str, version_dir: &Pat
 
 async fn download_checksums(
     cli: &Cli,
+    releases: &K3sReleases,
     version: &str,
     arch: &str,
     version_dir: &Path,
+    retry: RetryPolicy,
 ) -> Result<HashMap<String, String>> {
     let checksums_filename = format!("sha256sum-{arch}.txt");
-    let checksums_url = format!("{K3S_RELEASES_URL}/{version}/{checksums_filename}");
+    let checksums_url = releases.download_url(version, &checksums_filename);
     let checksums_path = version_dir.join(&checksums_filename);
 
     if !cli.quiet {
@@ -494,7 +551,7 @@ async fn download_checksums(
         Some(manager.add_download(&checksums_filename))
     };
 
-    download_with_progress(&checksums_url, &checksums_path, pb.as_ref(), cli).await?;
+    download_with_progress(&checksums_url, &checksums_path, pb.as_ref(), cli, retry).await?;
 
     if let Some(pb) = pb {
         DownloadManager::finish_success(&pb, &checksums_filename);
@@ -518,13 +575,16 @@ struct DownloadResult {
     verification: Option<Result<()>>,
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn download_remaining_files(
     cli: &Cli,
+    releases: &K3sReleases,
     version: &str,
     arch: &str,
     version_dir: &Path,
     checksums: &HashMap<String, String>,
     force: bool,
+    retry: RetryPolicy,
 ) -> Result<()> {
     let files = get_download_files(arch);
     let manager = DownloadManager::new();
@@ -549,11 +609,13 @@ async fn download_remaining_files(
             file_type,
             filename,
             pb,
+            releases,
             version,
             version_dir,
             arch,
             cli,
             checksums,
+            retry,
         )
     });
 
@@ -569,18 +631,30 @@ async fn download_and_verify(
     file_type: &'static str,
     filename: String,
     progress_bar: Option<ProgressBar>,
+    releases: &K3sReleases,
     version: &str,
     version_dir: &Path,
     arch: &str,
     cli: &Cli,
     checksums: &HashMap<String, String>,
+    retry: RetryPolicy,
 ) -> DownloadResult {
+    let progress = progress_bar.as_ref();
     let result = if file_type == "images" {
-        download_images_with_fallback(version, version_dir, arch, progress_bar.as_ref(), cli).await
+        download_images_with_fallback(
+            releases,
+            version,
+            version_dir,
+            &image_file_names(arch),
+            progress,
+            cli,
+            retry,
+        )
+        .await
     } else {
-        let url = format!("{K3S_RELEASES_URL}/{version}/{filename}");
+        let url = releases.download_url(version, &filename);
         let file_path = version_dir.join(&filename);
-        download_with_progress(&url, &file_path, progress_bar.as_ref(), cli).await
+        download_with_progress(&url, &file_path, progress, cli, retry).await
     };
 
     // Verify immediately after download completes (concurrent with other downloads)
@@ -650,11 +724,13 @@ fn process_download_results(results: Vec<DownloadResult>, cli: &Cli, force: bool
                                 DownloadManager::finish_success(pb, actual_filename.as_ref());
                             }
                         } else {
-                            // Normal mode: collect as error
+                            // Normal mode: collect as error, and remove the file
+                            // so the next run downloads it again
                             warn!(
                                 "Checksum verification failed for {}: {}",
                                 actual_filename, e
                             );
+                            cleanup_partial_download(&downloaded_path);
                             if let Some(pb) = &download.progress_bar {
                                 DownloadManager::finish_error(pb, actual_filename.as_ref());
                             }
@@ -750,11 +826,392 @@ fn verify_and_print_success(
     Ok(())
 }
 
+fn spinner(show: bool, message: &'static str) -> Option<ProgressBar> {
+    if !show {
+        return None;
+    }
+
+    let sp = ProgressBar::new_spinner();
+    sp.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.cyan} {msg}")
+            .expect("valid spinner template"),
+    );
+    sp.set_message(message);
+    sp.enable_steady_tick(std::time::Duration::from_millis(100));
+    Some(sp)
+}
+
+/// Output structure for the cache export and import commands.
+#[derive(Debug, Serialize)]
+pub struct BundleOutput {
+    /// Path to the bundle file
+    pub bundle: PathBuf,
+    /// Cache directory of the bundled version
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub manifest: BundleManifest,
+}
+
+/// Export a cached k3s version as an offline bundle
+#[allow(clippy::unused_async)] // Async required by command dispatch
+pub async fn export(cli: &Cli, version: &str, output: Option<&Path>) -> Result<()> {
+    validate_version(version)?;
+
+    let arch = arch_string();
+    let version_dir = k3s_version_cache_dir(version)?;
+    info!("Exporting k3s {version} from {}", version_dir.display());
+
+    if !version_dir.is_dir() {
+        return Err(anyhow!(
+            "k3s {version} is not cached. Run 'rh cache populate {version}' first."
+        ));
+    }
+
+    // Only bundle a cache that Rancher Desktop could use as-is, whichever
+    // image format it holds
+    if !verify::check_version(&version_dir, version, arch).is_verified() {
+        return Err(anyhow!(
+            "The cache for k3s {version} is incomplete or failed checksum verification.\n\
+             Run 'rh cache populate {version}' to repair it before exporting."
+        ));
+    }
+
+    let output = output.map_or_else(
+        || PathBuf::from(bundle::default_bundle_name(version, arch)),
+        Path::to_path_buf,
+    );
+
+    let sp = spinner(!cli.quiet && !cli.json, "Writing bundle...");
+    let result = bundle::export_bundle(&version_dir, version, arch, &output, chrono::Local::now());
+    if let Some(sp) = sp {
+        sp.finish_and_clear();
+    }
+    let manifest = result?;
+
+    if cli.json {
+        let output = BundleOutput {
+            bundle: output,
+            path: version_dir,
+            manifest,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !cli.quiet {
+        println!(
+            "{} Exported k3s {} ({} files, {}) to {}",
+            "\u{2714}".green(),
+            version.yellow(),
+            manifest.files.len(),
+            format_size(manifest.total_size()),
+            output.display()
+        );
+        println!();
+        println!(
+            "Copy the bundle to another machine and run {}",
+            format!("rh cache import {}", output.display()).cyan()
+        );
+    }
+
+    Ok(())
+}
+
+/// Install a k3s version into the cache from an offline bundle
+#[allow(clippy::unused_async)] // Async required by command dispatch
+pub async fn import(cli: &Cli, bundle_path: &Path, force: bool) -> Result<()> {
+    let cache_dir = k3s_cache_dir()?;
+    info!(
+        "Importing {} into {}",
+        bundle_path.display(),
+        cache_dir.display()
+    );
+
+    let sp = spinner(!cli.quiet && !cli.json, "Verifying and importing bundle...");
+    let result = bundle::import_bundle(bundle_path, &cache_dir, arch_string(), force);
+    if let Some(sp) = sp {
+        sp.finish_and_clear();
+    }
+    let (manifest, version_dir) = result?;
+
+    if cli.json {
+        let output = BundleOutput {
+            bundle: bundle_path.to_path_buf(),
+            path: version_dir,
+            manifest,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !cli.quiet {
+        println!(
+            "{} Imported k3s {} ({} files, {}), all checksums verified against sha256sum-{}.txt",
+            "\u{2714}".green(),
+            manifest.version.yellow(),
+            manifest.files.len(),
+            format_size(manifest.total_size()),
+            manifest.arch
+        );
+        println!();
+        println!("Cache location: {}", version_dir.display());
+    }
+
+    Ok(())
+}
+
+/// Output structure for the cache verify command.
+#[derive(Debug, Serialize)]
+pub struct VerifyOutput {
+    /// Path to the k3s cache directory
+    pub cache_dir: PathBuf,
+    /// Checked versions
+    pub versions: Vec<VersionCheck>,
+    /// Whether every version is intact
+    pub all_ok: bool,
+}
+
+/// Re-hash cached files against their checksums, and re-download corrupt or
+/// missing ones with the given retry policy if `repair` is set.
+///
+/// Checks `version`, or every cached version when it is `None`. Returns a
+/// [`CheckError`] if any file is still corrupt or missing.
+pub async fn verify(cli: &Cli, version: Option<&str>, repair: Option<RetryPolicy>) -> Result<()> {
+    let cache_dir = k3s_cache_dir()?;
+    let arch = arch_string();
+    info!("Verifying k3s cache in {}", cache_dir.display());
+
+    let versions = if let Some(version) = version {
+        validate_version(version)?;
+        if !cache_dir.join(version).is_dir() {
+            return Err(anyhow!(
+                "k3s {version} is not cached. Run 'rh cache populate {version}' to download it."
+            ));
+        }
+        vec![version.to_string()]
+    } else {
+        cached_version_names(&cache_dir)?
+    };
+
+    if versions.is_empty() && !cli.json {
+        if !cli.quiet {
+            println!("{}", "No cached versions to verify.".yellow());
+        }
+        return Ok(());
+    }
+
+    let releases = match repair {
+        Some(retry) => Some((K3sReleases::from_cli(cli)?, retry)),
+        None => None,
+    };
+    let show_progress = !cli.quiet && !cli.json;
+
+    let mut checks = Vec::new();
+    for version in &versions {
+        let sp = spinner(show_progress, "Verifying checksums...");
+        let mut check = verify::check_version(&cache_dir.join(version), version, arch);
+        if let Some(sp) = sp {
+            sp.finish_and_clear();
+        }
+
+        if let Some((releases, retry)) = &releases {
+            if !check.is_ok() {
+                check = repair_version(cli, releases, check, arch, *retry).await;
+            }
+        }
+
+        if show_progress {
+            print_version_check(&check);
+        }
+        checks.push(check);
+    }
+
+    let broken = checks.iter().flat_map(VersionCheck::broken).count();
+
+    if cli.json {
+        let output = VerifyOutput {
+            cache_dir,
+            versions: checks,
+            all_ok: broken == 0,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !cli.quiet {
+        if broken == 0 {
+            println!("{} All cached files verified", "\u{2714}".green());
+        } else {
+            println!("{} {broken} file(s) corrupt or missing", "\u{2718}".red());
+            if repair.is_none() {
+                let target = version.map_or_else(|| "--all".to_string(), ToString::to_string);
+                println!(
+                    "Run {} to download them again.",
+                    format!("rh cache verify {target} --repair").cyan()
+                );
+            }
+        }
+    }
+
+    match CheckError::from_counts(broken, 0, false) {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
+
+/// Names of the cached versions in `cache_dir`, newest first
+fn cached_version_names(cache_dir: &Path) -> Result<Vec<String>> {
+    if !cache_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut names: Vec<String> = fs::read_dir(cache_dir)
+        .with_context(|| format!("Failed to read cache directory: {}", cache_dir.display()))?
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    names.sort_by(|a, b| b.cmp(a));
+    Ok(names)
+}
+
+/// Download the broken files of `check` again and re-check the version
+async fn repair_version(
+    cli: &Cli,
+    releases: &K3sReleases,
+    check: VersionCheck,
+    arch: &str,
+    retry: RetryPolicy,
+) -> VersionCheck {
+    let version = check.version.clone();
+    let dir = check.path.clone();
+    let images = image_file_names(arch);
+    let manager = DownloadManager::new();
+    let mut errors: HashMap<String, String> = HashMap::new();
+
+    // Without a valid checksums file nothing else can be checked, so it goes first
+    let checksums_name = format!("sha256sum-{arch}.txt");
+    let mut broken: Vec<String> = check.broken().map(|f| f.name.clone()).collect();
+    broken.sort_by_key(|name| *name != checksums_name);
+
+    for name in &broken {
+        let path = dir.join(name);
+        cleanup_partial_download(&path);
+        discard_partial_download(&path);
+
+        let pb = if cli.quiet || cli.json {
+            None
+        } else {
+            Some(manager.add_download(name))
+        };
+        let progress = pb.as_ref();
+
+        let mut result = download_with_progress(
+            &releases.download_url(&version, name),
+            &path,
+            progress,
+            cli,
+            retry,
+        )
+        .await;
+
+        // A missing image may be published in another format by this release
+        let not_found = result.as_ref().is_err_and(|e| {
+            e.downcast_ref::<DownloadError>()
+                .is_some_and(DownloadError::is_not_found)
+        });
+        if not_found && images.contains(name) {
+            let others: Vec<String> = images.iter().filter(|n| *n != name).cloned().collect();
+            result = download_images_with_fallback(
+                releases, &version, &dir, &others, progress, cli, retry,
+            )
+            .await;
+        }
+
+        match (result, pb) {
+            (Ok(_), Some(pb)) => DownloadManager::finish_success(&pb, name),
+            (Err(e), pb) => {
+                if let Some(pb) = pb {
+                    DownloadManager::finish_error(&pb, name);
+                }
+                errors.insert(name.clone(), format!("{e:#}"));
+            }
+            (Ok(_), None) => {}
+        }
+    }
+
+    let images_repaired = broken.iter().any(|name| images.contains(name));
+    let mut repaired = verify::check_version(&dir, &version, arch);
+    for file in &mut repaired.files {
+        let was_broken =
+            broken.contains(&file.name) || (images_repaired && images.contains(&file.name));
+        if file.status == FileStatus::Ok && was_broken {
+            file.status = FileStatus::Repaired;
+        }
+        if let Some(error) = errors.remove(&file.name) {
+            file.error.get_or_insert(error);
+        }
+    }
+    repaired
+}
+
+fn print_version_check(check: &VersionCheck) {
+    let status = if check.is_ok() {
+        "\u{2714}".green() // ✔
+    } else {
+        "\u{2718}".red() // ✘
+    };
+    println!("{} {}", status, check.version.bold());
+
+    for file in &check.files {
+        let file_status = match file.status {
+            FileStatus::Ok => "ok".green(),
+            FileStatus::Repaired => "repaired".green(),
+            FileStatus::Unchecked => "unchecked (no checksum)".yellow(),
+            FileStatus::Corrupt => "corrupt".red(),
+            FileStatus::Missing => "missing".red(),
+        };
+        println!("    {} ({})", file.name, file_status);
+        if let (Some(expected), Some(actual)) = (&file.expected, &file.actual) {
+            println!("      expected {expected}");
+            println!("      actual   {actual}");
+        }
+        if let Some(error) = &file.error {
+            println!("      {}", error.red());
+        }
+    }
+    println!();
+}
+
+/// Download `url` to `path`, retrying transient failures according to `retry`.
+///
+/// Each retry resumes from the partial file left by the failed attempt.
 async fn download_with_progress(
     url: &str,
     path: &Path,
     progress: Option<&ProgressBar>,
     cli: &Cli,
+    retry: RetryPolicy,
+) -> Result<PathBuf> {
+    let mut attempt = 0;
+    loop {
+        match download_once(url, path, progress, cli).await {
+            Err(e) if attempt < retry.retries && is_retryable(&e) => {
+                attempt += 1;
+                let delay = retry.next_delay(attempt);
+                info!(
+                    "Download of {} failed ({:#}), retry {}/{} in {:?}",
+                    url, e, attempt, retry.retries, delay
+                );
+                if let Some(pb) = progress {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    DownloadManager::show_retry(pb, &name, attempt, retry.retries, delay);
+                }
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+async fn download_once(
+    url: &str,
+    path: &Path,
+    progress: Option<&ProgressBar>,
+    cli: &Cli,
 ) -> Result<PathBuf> {
     debug!("Downloading {} to {}", url, path.display());
 
@@ -762,45 +1219,117 @@ async fn download_with_progress(
         return Ok(existing);
     }
 
-    let response = crate::client::http::request_with_cert_handling(
-        url,
-        &HttpClientConfig::for_downloads_with_timeout(cli.insecure, cli.download_timeout),
-    )
-    .await?;
+    let resume = resumable_download(path, url);
+
+    // A previous run may have received every byte but stopped before finishing
+    if let Some((offset, partial)) = &resume {
+        if partial.total_size == Some(*offset) {
+            finish_partial_download(path)?;
+            return Ok(path.to_path_buf());
+        }
+    }
+
+    let (response, offset, partial) = start_download(url, path, resume, cli).await?;
 
-    let total_size = response.content_length();
     if let Some(pb) = progress {
-        if let Some(size) = total_size {
+        if let Some(size) = partial.total_size {
             pb.set_length(size);
         }
+        pb.set_position(offset);
     }
 
-    // Stream to file, cleaning up partial file on error
-    if let Err(e) = stream_to_file(response, path, progress).await {
-        cleanup_partial_download(path);
-        return Err(e);
-    }
+    // Keep the partial file on error so the next attempt can resume it
+    save_partial_download(path, &partial)?;
+    stream_to_file(response, &part_path(path), offset, progress)
+        .await
+        .context("Download interrupted; run the command again to resume it")?;
+    finish_partial_download(path)?;
 
     Ok(path.to_path_buf())
 }
 
+/// Request `url`, resuming the partial download of `path` when there is one.
+///
+/// Returns the response, the offset its body starts at, and the metadata to
+/// save for the partial file. A partial file the server will not resume is
+/// discarded and the download starts over.
+async fn start_download(
+    url: &str,
+    path: &Path,
+    resume: Option<(u64, PartialDownload)>,
+    cli: &Cli,
+) -> Result<(reqwest::Response, u64, PartialDownload)> {
+    let config = HttpClientConfig::for_downloads_with_timeout(cli.insecure, cli.download_timeout);
+
+    if let Some((offset, partial)) = resume {
+        debug!("Resuming {} from byte {}", path.display(), offset);
+        let response = crate::client::http::request_with_cert_handling(
+            url,
+            &partial.resume_headers(offset),
+            &config,
+        )
+        .await?;
+
+        match response.status() {
+            StatusCode::PARTIAL_CONTENT
+                if content_range_start(response.headers()) == Some(offset) =>
+            {
+                return Ok((response, offset, partial));
+            }
+            // If-Range did not match: the file changed, so this is the whole new file
+            status if status.is_success() && status != StatusCode::PARTIAL_CONTENT => {
+                info!("{} changed on the server, restarting download", url);
+                let partial = PartialDownload::from_response(url, &response);
+                return Ok((response, 0, partial));
+            }
+            // The partial data cannot be continued from: restart from scratch
+            status @ (StatusCode::PARTIAL_CONTENT | StatusCode::RANGE_NOT_SATISFIABLE) => {
+                info!(
+                    "Could not resume {} (HTTP {}), restarting download",
+                    url, status
+                );
+                discard_partial_download(path);
+            }
+            // Keep the partial file so a retry can resume from it
+            status => {
+                return Err(DownloadError::Status {
+                    url: url.to_string(),
+                    status,
+                }
+                .into());
+            }
+        }
+    }
+
+    let response =
+        crate::client::http::request_with_cert_handling(url, &HeaderMap::new(), &config).await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(DownloadError::Status {
+            url: url.to_string(),
+            status,
+        }
+        .into());
+    }
+
+    let partial = PartialDownload::from_response(url, &response);
+    Ok((response, 0, partial))
+}
+
+/// Download the airgap images in the first of `formats` the release publishes
 async fn download_images_with_fallback(
+    releases: &K3sReleases,
     version: &str,
     version_dir: &Path,
-    arch: &str,
+    formats: &[String],
     progress: Option<&ProgressBar>,
     cli: &Cli,
+    retry: RetryPolicy,
 ) -> Result<PathBuf> {
-    let formats = [
-        format!("k3s-airgap-images-{arch}.tar.zst"),
-        format!("k3s-airgap-images-{arch}.tar.gz"),
-        format!("k3s-airgap-images-{arch}.tar"),
-    ];
-
     let mut last_error = None;
 
-    for filename in &formats {
-        let url = format!("{K3S_RELEASES_URL}/{version}/{filename}");
+    for filename in formats {
+        let url = releases.download_url(version, filename);
         let file_path = version_dir.join(filename);
 
         debug!("Trying to download images: {}", url);
@@ -809,16 +1338,20 @@ async fn download_images_with_fallback(
             return Ok(existing);
         }
 
-        match download_with_progress(&url, &file_path, progress, cli).await {
+        match download_with_progress(&url, &file_path, progress, cli, retry).await {
             Ok(path) => {
                 info!("Successfully downloaded images: {}", filename);
                 return Ok(path);
             }
-            Err(e) => {
+            // Only a missing file means this release uses another format
+            Err(e)
+                if e.downcast_ref::<DownloadError>()
+                    .is_some_and(DownloadError::is_not_found) =>
+            {
                 debug!("Failed to download {}: {}", filename, e);
                 last_error = Some(e);
-                cleanup_partial_download(&file_path);
             }
+            Err(e) => return Err(e),
         }
     }
 
@@ -829,6 +1362,53 @@ async fn download_images_with_fallback(
 mod tests {
     use super::*;
 
+    fn cached(version: &str) -> CachedVersion {
+        CachedVersion {
+            version: version.to_string(),
+            path: PathBuf::from(version),
+            files: vec![CachedFile {
+                name: "k3s".to_string(),
+                size: 1,
+                verified: Some(true),
+            }],
+            complete: true,
+        }
+    }
+
+    #[test]
+    fn test_find_cached_version() {
+        let versions = vec![
+            cached("v1.33.3+k3s2"),
+            cached("v1.33.3+k3s1"),
+            cached("v1.32.1+k3s1"),
+        ];
+        assert_eq!(
+            find_cached_version(&versions, "1.33.3").map(|v| v.version.as_str()),
+            Some("v1.33.3+k3s2")
+        );
+        assert_eq!(
+            find_cached_version(&versions, "v1.32.1").map(|v| v.version.as_str()),
+            Some("v1.32.1+k3s1")
+        );
+        assert!(find_cached_version(&versions, "1.33").is_none());
+        assert!(versions[0].verified());
+    }
+
+    #[test]
+    fn test_verified_ignores_unchecked_files() {
+        let mut version = cached("v1.33.3+k3s1");
+        version.files[0].name = k3s_binary_name().to_string();
+        version.files.push(CachedFile {
+            name: format!("sha256sum-{}.txt", arch_string()),
+            size: 1,
+            verified: None,
+        });
+        assert!(version.verified());
+
+        version.files[0].verified = Some(false);
+        assert!(!version.verified());
+    }
+
     #[test]
     fn test_validate_version_valid() {
         assert!(validate_version("v1.28.3+k3s1").is_ok());
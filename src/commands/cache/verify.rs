@@ -0,0 +1,292 @@
+//! Integrity checks for cached k3s versions.
+//!
+//! Every file in a version directory that is listed in the version's
+//! `sha256sum-<arch>.txt` is re-hashed, along with the binary and airgap
+//! images Rancher Desktop needs, which are reported missing if absent.
+
+use super::image_file_names;
+use crate::paths::k3s_binary_name;
+use crate::utils::checksum::{calculate_file_hash, parse_checksum_file};
+use crate::utils::download::is_partial_download;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Result of checking one cached file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    /// Matches its checksum
+    Ok,
+    /// Does not match its checksum, or could not be read
+    Corrupt,
+    /// Required but not present
+    Missing,
+    /// Present, but there is no checksum to compare against
+    Unchecked,
+    /// Was corrupt or missing and has been downloaded again
+    Repaired,
+}
+
+impl FileStatus {
+    /// Whether the file needs to be downloaded again
+    #[must_use]
+    pub fn is_broken(self) -> bool {
+        matches!(self, Self::Corrupt | Self::Missing)
+    }
+}
+
+impl std::fmt::Display for FileStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ok => write!(f, "ok"),
+            Self::Corrupt => write!(f, "corrupt"),
+            Self::Missing => write!(f, "missing"),
+            Self::Unchecked => write!(f, "unchecked"),
+            Self::Repaired => write!(f, "repaired"),
+        }
+    }
+}
+
+/// A checked file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileCheck {
+    pub name: String,
+    pub status: FileStatus,
+    /// Hash from the checksums file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// Hash of the file on disk, when it differs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl FileCheck {
+    fn new(name: &str, status: FileStatus) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            expected: None,
+            actual: None,
+            error: None,
+        }
+    }
+
+    fn with_error(mut self, error: impl std::fmt::Display) -> Self {
+        self.error = Some(error.to_string());
+        self
+    }
+}
+
+/// The checked files of one cached version
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionCheck {
+    pub version: String,
+    pub path: PathBuf,
+    pub files: Vec<FileCheck>,
+}
+
+impl VersionCheck {
+    /// Files that need to be downloaded again
+    pub fn broken(&self) -> impl Iterator<Item = &FileCheck> {
+        self.files.iter().filter(|f| f.status.is_broken())
+    }
+
+    /// Whether every required file is present and none failed its checksum
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.broken().next().is_none()
+    }
+
+    /// Whether every file, including the binary and images, matched a checksum
+    #[must_use]
+    pub fn is_verified(&self) -> bool {
+        self.files.iter().all(|f| f.status == FileStatus::Ok)
+    }
+}
+
+/// Check the files of the cached `version` in `dir` for `arch`
+#[must_use]
+pub fn check_version(dir: &Path, version: &str, arch: &str) -> VersionCheck {
+    let checksums_name = format!("sha256sum-{arch}.txt");
+    let (checksums_check, checksums) = read_checksums(&dir.join(&checksums_name), &checksums_name);
+
+    // Releases ship the images in one of several formats
+    let images = image_file_names(arch);
+    let images_name = images
+        .iter()
+        .find(|name| dir.join(name).is_file())
+        .unwrap_or(&images[0]);
+
+    let mut names = vec![k3s_binary_name().to_string(), images_name.clone()];
+    let mut others: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| {
+            !names.contains(name)
+                && *name != checksums_name
+                && !name.starts_with('.')
+                && !is_partial_download(name)
+                && checksums.as_ref().is_some_and(|cs| cs.contains_key(name))
+        })
+        .collect();
+    others.sort();
+    names.extend(others);
+
+    let mut files = vec![checksums_check];
+    files.extend(
+        names
+            .iter()
+            .map(|name| check_file(&dir.join(name), name, checksums.as_ref())),
+    );
+
+    VersionCheck {
+        version: version.to_string(),
+        path: dir.to_path_buf(),
+        files,
+    }
+}
+
+fn read_checksums(path: &Path, name: &str) -> (FileCheck, Option<HashMap<String, String>>) {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return (FileCheck::new(name, FileStatus::Missing), None)
+        }
+        Err(e) => {
+            return (
+                FileCheck::new(name, FileStatus::Corrupt).with_error(e),
+                None,
+            )
+        }
+    };
+
+    // The checksums file is the reference, so it is only checked for validity
+    match parse_checksum_file(&content) {
+        Ok(checksums) => (FileCheck::new(name, FileStatus::Ok), Some(checksums)),
+        Err(e) => (
+            FileCheck::new(name, FileStatus::Corrupt).with_error(e),
+            None,
+        ),
+    }
+}
+
+fn check_file(path: &Path, name: &str, checksums: Option<&HashMap<String, String>>) -> FileCheck {
+    if !path.is_file() {
+        return FileCheck::new(name, FileStatus::Missing);
+    }
+
+    let Some(expected) = checksums.and_then(|cs| cs.get(name)) else {
+        return FileCheck::new(name, FileStatus::Unchecked);
+    };
+
+    let mut check = match calculate_file_hash(path) {
+        Ok(actual) if actual == *expected => FileCheck::new(name, FileStatus::Ok),
+        Ok(actual) => FileCheck {
+            actual: Some(actual),
+            ..FileCheck::new(name, FileStatus::Corrupt)
+        },
+        Err(e) => FileCheck::new(name, FileStatus::Corrupt).with_error(format!("{e:#}")),
+    };
+    check.expected = Some(expected.clone());
+    check
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARCH: &str = "amd64";
+
+    fn write_version(dir: &Path, files: &[(&str, &[u8])]) {
+        let mut sums = Vec::new();
+        for (name, content) in files {
+            let path = dir.join(name);
+            fs::write(&path, content).unwrap();
+            sums.push(format!("{}  {name}\n", calculate_file_hash(&path).unwrap()));
+        }
+        let sums = sums.concat();
+        fs::write(dir.join(format!("sha256sum-{ARCH}.txt")), sums).unwrap();
+    }
+
+    fn statuses(check: &VersionCheck) -> Vec<(&str, FileStatus)> {
+        check
+            .files
+            .iter()
+            .map(|f| (f.name.as_str(), f.status))
+            .collect()
+    }
+
+    #[test]
+    fn test_check_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = k3s_binary_name();
+        write_version(
+            dir.path(),
+            &[
+                (binary, b"k3s"),
+                ("k3s-airgap-images-amd64.tar.gz", b"images"),
+                ("k3s-images.txt", b"docker.io/rancher/mirrored-pause"),
+            ],
+        );
+        fs::write(dir.path().join("unlisted.txt"), b"extra").unwrap();
+
+        let check = check_version(dir.path(), "v1.33.3+k3s1", ARCH);
+        assert!(check.is_ok());
+        assert!(check.is_verified());
+        assert_eq!(
+            statuses(&check),
+            [
+                ("sha256sum-amd64.txt", FileStatus::Ok),
+                (binary, FileStatus::Ok),
+                ("k3s-airgap-images-amd64.tar.gz", FileStatus::Ok),
+                ("k3s-images.txt", FileStatus::Ok),
+            ]
+        );
+
+        fs::write(dir.path().join(binary), b"tampered").unwrap();
+        fs::remove_file(dir.path().join("k3s-airgap-images-amd64.tar.gz")).unwrap();
+
+        let check = check_version(dir.path(), "v1.33.3+k3s1", ARCH);
+        assert!(!check.is_ok());
+        let broken: Vec<_> = check
+            .broken()
+            .map(|f| (f.name.as_str(), f.status))
+            .collect();
+        assert_eq!(
+            broken,
+            [
+                (binary, FileStatus::Corrupt),
+                ("k3s-airgap-images-amd64.tar.zst", FileStatus::Missing),
+            ]
+        );
+        let corrupt = &check.files[1];
+        assert_ne!(corrupt.expected, corrupt.actual);
+    }
+
+    #[test]
+    fn test_check_version_without_checksums() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(k3s_binary_name()), b"k3s").unwrap();
+
+        let check = check_version(dir.path(), "v1.33.3+k3s1", ARCH);
+        assert_eq!(check.files[0].status, FileStatus::Missing);
+        assert_eq!(check.files[1].status, FileStatus::Unchecked);
+        assert!(!check.is_verified());
+
+        fs::write(
+            dir.path().join("sha256sum-amd64.txt"),
+            "not a checksum file",
+        )
+        .unwrap();
+        let check = check_version(dir.path(), "v1.33.3+k3s1", ARCH);
+        assert_eq!(check.files[0].status, FileStatus::Corrupt);
+        assert!(check.files[0].error.is_some());
+    }
+}
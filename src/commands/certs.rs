@@ -5,7 +5,9 @@
 
 use crate::cli::Cli;
 use crate::client::http::is_proxy_issuer;
-use crate::constants::{extract_domain, REQUIRED_ENDPOINTS};
+use crate::constants::extract_domain;
+use crate::exit::CheckError;
+use crate::releases::K3sReleases;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use colored::Colorize;
@@ -72,11 +74,15 @@ pub struct CertsCheckOutput {
     pub recommendations: Vec<String>,
 }
 
-/// Check SSL certificates for required domains
-pub async fn check(cli: &Cli) -> Result<()> {
+/// Check SSL certificates for required domains.
+///
+/// Returns a [`CheckError`] if any domain failed, or if `strict` and a
+/// corporate SSL inspection proxy was detected.
+pub async fn check(cli: &Cli, strict: bool) -> Result<()> {
     info!("Starting certificate check for required domains");
 
     let show_progress = !cli.quiet && !cli.json;
+    let endpoints = K3sReleases::from_cli(cli)?.endpoints();
 
     if show_progress {
         println!("{}", "SSL Certificate Check".bold().cyan());
@@ -86,8 +92,10 @@ pub async fn check(cli: &Cli) -> Result<()> {
     }
 
     // Check all domains concurrently for better performance
-    let futures: Vec<_> = REQUIRED_ENDPOINTS
+    // Plain HTTP mirrors have no certificate to check
+    let futures: Vec<_> = endpoints
         .iter()
+        .filter(|(_, url)| url.starts_with("https://"))
         .map(|(name, url)| {
             debug!("Checking endpoint: {} ({})", name, url);
             check_endpoint(name, url, cli.insecure)
@@ -104,8 +112,9 @@ pub async fn check(cli: &Cli) -> Result<()> {
         }
     }
 
-    let all_ok = results.iter().all(|r| r.success);
-    let recommendations = generate_recommendations(&results, any_proxy_detected);
+    let failed = results.iter().filter(|r| !r.success).count();
+    let all_ok = failed == 0;
+    let recommendations = generate_recommendations(&results, &endpoints, any_proxy_detected);
 
     if cli.json {
         let output = CertsCheckOutput {
@@ -120,11 +129,16 @@ pub async fn check(cli: &Cli) -> Result<()> {
         print_summary(all_ok, any_proxy_detected, &recommendations);
     }
 
-    Ok(())
+    match CheckError::from_counts(failed, usize::from(any_proxy_detected), strict) {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
 }
 
-/// Check a single endpoint's certificate
-async fn check_endpoint(name: &str, url: &str, insecure: bool) -> CertCheckResult {
+/// Connect to an endpoint over TLS and report its certificate chain.
+///
+/// Never fails; connection and handshake errors are reported in the result.
+pub async fn check_endpoint(name: &str, url: &str, insecure: bool) -> CertCheckResult {
     let Some(domain) = extract_domain(url) else {
         return CertCheckResult {
             domain: name.to_string(),
@@ -135,7 +149,13 @@ async fn check_endpoint(name: &str, url: &str, insecure: bool) -> CertCheckResul
         };
     };
 
-    match check_domain_inner(&domain, insecure).await {
+    // Mirrors may serve TLS on a non-default port
+    let port = url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.port())
+        .unwrap_or(443);
+
+    match check_domain_inner(&domain, port, insecure).await {
         Ok((cert_info, proxy_detected)) => CertCheckResult {
             domain: format!("{name} ({domain})"),
             success: true,
@@ -157,7 +177,11 @@ async fn check_endpoint(name: &str, url: &str, insecure: bool) -> CertCheckResul
 }
 
 /// Inner function that does the actual certificate check
-async fn check_domain_inner(domain: &str, insecure: bool) -> Result<(CertificateInfo, bool)> {
+async fn check_domain_inner(
+    domain: &str,
+    port: u16,
+    insecure: bool,
+) -> Result<(CertificateInfo, bool)> {
     // Install the ring crypto provider exactly once
     CRYPTO_PROVIDER_INIT.call_once(|| {
         let _ = rustls::crypto::ring::default_provider().install_default();
@@ -187,7 +211,7 @@ async fn check_domain_inner(domain: &str, insecure: bool) -> Result<(Certificate
     let connector = TlsConnector::from(Arc::new(config));
 
     // Connect with timeout
-    let addr = format!("{domain}:443");
+    let addr = format!("{domain}:{port}");
     let stream = tokio::time::timeout(
         Duration::from_secs(CONNECT_TIMEOUT_SECS),
         TcpStream::connect(&addr),
@@ -376,7 +400,11 @@ fn print_summary(all_ok: bool, proxy_detected: bool, recommendations: &[String])
 }
 
 /// Generate recommendations based on check results
-fn generate_recommendations(results: &[CertCheckResult], proxy_detected: bool) -> Vec<String> {
+fn generate_recommendations(
+    results: &[CertCheckResult],
+    endpoints: &[(String, String)],
+    proxy_detected: bool,
+) -> Vec<String> {
     let mut recommendations = Vec::new();
 
     let failed_count = results.iter().filter(|r| !r.success).count();
@@ -390,7 +418,7 @@ fn generate_recommendations(results: &[CertCheckResult], proxy_detected: bool) -
     if proxy_detected {
         recommendations
             .push("Contact your IT department to whitelist the following URLs:".to_string());
-        for (name, url) in REQUIRED_ENDPOINTS {
+        for (name, url) in endpoints {
             recommendations.push(format!("    - {name}: {url}"));
         }
         recommendations.push(
@@ -497,7 +525,8 @@ mod tests {
             make_success_result("github.com", false),
             make_success_result("api.github.com", false),
         ];
-        let recommendations = generate_recommendations(&results, false);
+        let recommendations =
+            generate_recommendations(&results, &K3sReleases::GitHub.endpoints(), false);
         assert!(recommendations.is_empty());
     }
 
@@ -507,7 +536,8 @@ mod tests {
             make_success_result("github.com", false),
             make_failure_result("api.github.com"),
         ];
-        let recommendations = generate_recommendations(&results, false);
+        let recommendations =
+            generate_recommendations(&results, &K3sReleases::GitHub.endpoints(), false);
         assert!(!recommendations.is_empty());
         assert!(recommendations
             .iter()
@@ -520,7 +550,8 @@ mod tests {
             make_success_result("github.com", true),
             make_success_result("api.github.com", true),
         ];
-        let recommendations = generate_recommendations(&results, true);
+        let recommendations =
+            generate_recommendations(&results, &K3sReleases::GitHub.endpoints(), true);
         assert!(!recommendations.is_empty());
         assert!(recommendations.iter().any(|r| r.contains("IT department")));
     }
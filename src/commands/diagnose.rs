@@ -3,10 +3,13 @@
 //! Runs multiple checks to verify Rancher Desktop health and identify issues.
 
 use crate::cli::Cli;
-use crate::client::http::{build_client, HttpClientConfig};
+use crate::client::http::{build_client, HttpClientConfig, HttpClientError};
+use crate::client::rd::{BackendState, RdClient, RdClientError};
+use crate::commands::settings::locked::{self, DeploymentProfile, ProfileScope};
 use crate::config::{ConfigError, RdEngineConfig};
-use crate::constants::REQUIRED_ENDPOINTS;
+use crate::exit::CheckError;
 use crate::paths::{arch_string, k3s_cache_dir};
+use crate::releases::K3sReleases;
 use anyhow::Result;
 use colored::Colorize;
 use serde::Serialize;
@@ -52,7 +55,7 @@ pub struct CheckResult {
 }
 
 impl CheckResult {
-    fn ok(name: impl Into<String>, message: impl Into<String>) -> Self {
+    pub fn ok(name: impl Into<String>, message: impl Into<String>) -> Self {
         Self {
             name: name.into(),
             status: CheckStatus::Ok,
@@ -61,7 +64,7 @@ impl CheckResult {
         }
     }
 
-    fn warn(name: impl Into<String>, message: impl Into<String>) -> Self {
+    pub fn warn(name: impl Into<String>, message: impl Into<String>) -> Self {
         Self {
             name: name.into(),
             status: CheckStatus::Warn,
@@ -70,7 +73,7 @@ impl CheckResult {
         }
     }
 
-    fn fail(name: impl Into<String>, message: impl Into<String>) -> Self {
+    pub fn fail(name: impl Into<String>, message: impl Into<String>) -> Self {
         Self {
             name: name.into(),
             status: CheckStatus::Fail,
@@ -79,7 +82,7 @@ impl CheckResult {
         }
     }
 
-    fn skip(name: impl Into<String>, message: impl Into<String>) -> Self {
+    pub fn skip(name: impl Into<String>, message: impl Into<String>) -> Self {
         Self {
             name: name.into(),
             status: CheckStatus::Skip,
@@ -88,7 +91,8 @@ impl CheckResult {
         }
     }
 
-    fn with_details(mut self, details: impl Into<String>) -> Self {
+    #[must_use]
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
         self.details = Some(details.into());
         self
     }
@@ -113,8 +117,12 @@ pub struct DiagnoseSummary {
     pub skip: usize,
 }
 
-/// Run comprehensive diagnostic checks
-pub async fn run(cli: &Cli) -> Result<()> {
+/// Run comprehensive diagnostic checks.
+///
+/// Returns a [`CheckError`] if any check failed, or if `strict` and any check
+/// reported a warning. Failures caused by Rancher Desktop not running are
+/// reported as [`CheckError::NotRunning`].
+pub async fn run(cli: &Cli, strict: bool) -> Result<()> {
     info!("Running diagnostic checks");
 
     let show_progress = !cli.quiet && !cli.json;
@@ -128,7 +136,7 @@ pub async fn run(cli: &Cli) -> Result<()> {
     let mut categories: HashMap<String, Vec<CheckResult>> = HashMap::new();
 
     // 1. Application Status
-    let application_checks = check_application_status(cli, show_progress).await;
+    let (application_checks, not_running) = check_application_status(cli, show_progress).await;
     let rd_running = application_checks
         .iter()
         .any(|c| c.name == "Rancher Desktop" && c.status == CheckStatus::Ok);
@@ -151,15 +159,19 @@ pub async fn run(cli: &Cli) -> Result<()> {
     };
     categories.insert("API Connectivity".to_string(), connectivity_checks);
 
-    // 3. Cache Status
+    // 3. Deployment Profile
+    let profile_checks = check_deployment_profile(cli, rd_running, show_progress).await;
+    categories.insert("Deployment Profile".to_string(), profile_checks);
+
+    // 4. Cache Status
     let cache_checks = check_cache_status(show_progress);
     categories.insert("Cache Status".to_string(), cache_checks);
 
-    // 4. Network Connectivity
+    // 5. Network Connectivity
     let network_checks = check_network_connectivity(cli, show_progress).await;
     categories.insert("Network Connectivity".to_string(), network_checks);
 
-    // 5. Platform-specific checks
+    // 6. Platform-specific checks
     let platform_checks = check_platform_specific(show_progress);
     categories.insert("Platform".to_string(), platform_checks);
 
@@ -214,7 +226,13 @@ pub async fn run(cli: &Cli) -> Result<()> {
         }
     }
 
-    Ok(())
+    if not_running {
+        return Err(CheckError::NotRunning.into());
+    }
+    match CheckError::from_counts(fail, warn, strict) {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
 }
 
 fn print_category_header(name: &str) {
@@ -237,7 +255,9 @@ fn print_check_result(check: &CheckResult) {
 }
 
 /// Check if Rancher Desktop is running and accessible
-async fn check_application_status(cli: &Cli, show_progress: bool) -> Vec<CheckResult> {
+/// Returns the checks, and whether they show Rancher Desktop is not running
+/// (no rd-engine.json, or its API refuses connections)
+async fn check_application_status(cli: &Cli, show_progress: bool) -> (Vec<CheckResult>, bool) {
     let mut results = Vec::new();
 
     if show_progress {
@@ -245,7 +265,8 @@ async fn check_application_status(cli: &Cli, show_progress: bool) -> Vec<CheckRe
     }
 
     // Check if rd-engine.json exists
-    let config_result = RdEngineConfig::load();
+    let config_result = RdEngineConfig::load_from(cli.config.as_deref());
+    let mut not_running = matches!(config_result, Err(ConfigError::NotFound { .. }));
     let rd_check = match &config_result {
         Ok(config) => {
             debug!(
@@ -278,18 +299,20 @@ async fn check_application_status(cli: &Cli, show_progress: bool) -> Vec<CheckRe
         results.push(tcp_check);
 
         // Try an HTTP request to the API
-        let http_check = check_http_api(&config, cli).await;
+        let (http_check, refused) = check_http_api(&config, cli).await;
         if show_progress {
             print_check_result(&http_check);
         }
         results.push(http_check);
+        // A stale rd-engine.json is left behind when Rancher Desktop exits uncleanly
+        not_running |= refused;
     }
 
     if show_progress {
         println!();
     }
 
-    results
+    (results, not_running)
 }
 
 fn check_tcp_port(host: &str, port: u16) -> CheckResult {
@@ -308,38 +331,38 @@ fn check_tcp_port(host: &str, port: u16) -> CheckResult {
     }
 }
 
-async fn check_http_api(config: &RdEngineConfig, cli: &Cli) -> CheckResult {
+/// Returns the check, and whether the API refused the connection
+async fn check_http_api(config: &RdEngineConfig, cli: &Cli) -> (CheckResult, bool) {
     let client_config = HttpClientConfig::with_timeout(cli.insecure, cli.timeout);
-    let client = match build_client(&client_config) {
+    let client = match RdClient::new(config.clone(), &client_config) {
         Ok(c) => c,
-        Err(e) => {
-            return CheckResult::fail("API Response", format!("Failed to build HTTP client: {e}"))
-        }
+        Err(e) => return (CheckResult::fail("API Response", e.to_string()), false),
     };
 
-    let url = config.api_url("/v1/settings");
     // Note: timeout is already configured on the client via HttpClientConfig
-    match client
-        .get(&url)
-        .header("Authorization", config.basic_auth())
-        .send()
-        .await
-    {
-        Ok(response) => {
-            let status = response.status();
-            if status.is_success() {
-                CheckResult::ok("API Response", format!("API responding (HTTP {status})"))
-            } else if status.as_u16() == 401 {
-                CheckResult::warn("API Response", "Authentication required")
-                    .with_details("Check rd-engine.json credentials")
-            } else {
-                CheckResult::warn("API Response", format!("Unexpected status: {status}"))
-            }
+    let result = client.settings().await;
+    let refused = matches!(
+        result,
+        Err(RdClientError::Http(HttpClientError::ConnectionRefused))
+    );
+    let check = match result {
+        Ok(_) => CheckResult::ok("API Response", "API responding"),
+        Err(RdClientError::Status { status, .. }) if status.as_u16() == 401 => {
+            CheckResult::warn("API Response", "Authentication required")
+                .with_details("Check rd-engine.json credentials")
+        }
+        Err(RdClientError::Status { status, .. }) => {
+            CheckResult::warn("API Response", format!("Unexpected status: {status}"))
+        }
+        Err(RdClientError::InvalidResponse { reason, .. }) => {
+            CheckResult::warn("API Response", "API responding with unexpected content")
+                .with_details(reason)
         }
         Err(e) => {
             CheckResult::fail("API Response", "API request failed").with_details(e.to_string())
         }
-    }
+    };
+    (check, refused)
 }
 
 /// Check API connectivity (backend state, version, etc.)
@@ -350,7 +373,7 @@ async fn check_api_connectivity(cli: &Cli, show_progress: bool) -> Vec<CheckResu
         print_category_header("API Connectivity");
     }
 
-    let Ok(config) = RdEngineConfig::load() else {
+    let Ok(config) = RdEngineConfig::load_from(cli.config.as_deref()) else {
         let skip = CheckResult::skip("Backend State", "Rancher Desktop not running");
         if show_progress {
             print_check_result(&skip);
@@ -360,10 +383,10 @@ async fn check_api_connectivity(cli: &Cli, show_progress: bool) -> Vec<CheckResu
     };
 
     let client_config = HttpClientConfig::with_timeout(cli.insecure, cli.timeout);
-    let client = match build_client(&client_config) {
+    let client = match RdClient::new(config, &client_config) {
         Ok(c) => c,
         Err(e) => {
-            let fail = CheckResult::fail("HTTP Client", format!("Failed to build client: {e}"));
+            let fail = CheckResult::fail("HTTP Client", e.to_string());
             if show_progress {
                 print_check_result(&fail);
                 println!();
@@ -373,14 +396,14 @@ async fn check_api_connectivity(cli: &Cli, show_progress: bool) -> Vec<CheckResu
     };
 
     // Check backend state via /v1/backend_state
-    let backend_check = check_backend_state(&client, &config).await;
+    let backend_check = check_backend_state(&client).await;
     if show_progress {
         print_check_result(&backend_check);
     }
     results.push(backend_check);
 
     // Check version info
-    let version_check = check_version_info(&client, &config).await;
+    let version_check = check_version_info(&client).await;
     if show_progress {
         print_check_result(&version_check);
     }
@@ -393,79 +416,127 @@ async fn check_api_connectivity(cli: &Cli, show_progress: bool) -> Vec<CheckResu
     results
 }
 
-async fn check_backend_state(client: &reqwest::Client, config: &RdEngineConfig) -> CheckResult {
-    let url = config.api_url("/v1/backend_state");
+async fn check_backend_state(client: &RdClient) -> CheckResult {
     // Note: timeout is already configured on the client via HttpClientConfig
-    match client
-        .get(&url)
-        .header("Authorization", config.basic_auth())
-        .send()
-        .await
-    {
-        Ok(response) => {
-            if response.status().is_success() {
-                match response.text().await {
-                    Ok(body) => {
-                        // Parse the state - typically "STARTED", "STOPPED", etc.
-                        let state = body.trim().trim_matches('"');
-                        if state.to_uppercase() == "STARTED" {
-                            CheckResult::ok("Backend State", "Backend is running")
-                        } else {
-                            CheckResult::warn("Backend State", format!("Backend state: {state}"))
-                        }
-                    }
-                    Err(e) => CheckResult::warn("Backend State", "Could not read response")
-                        .with_details(e.to_string()),
-                }
-            } else {
-                CheckResult::warn("Backend State", format!("HTTP {}", response.status()))
-            }
+    match client.backend_state().await {
+        Ok(BackendState::Started) => CheckResult::ok("Backend State", "Backend is running"),
+        Ok(state) => CheckResult::warn("Backend State", format!("Backend state: {state}")),
+        Err(RdClientError::Status { status, .. }) => {
+            CheckResult::warn("Backend State", format!("HTTP {status}"))
+        }
+        Err(e @ RdClientError::InvalidResponse { .. }) => {
+            CheckResult::warn("Backend State", "Could not read response")
+                .with_details(e.to_string())
         }
         Err(e) => CheckResult::fail("Backend State", "Request failed").with_details(e.to_string()),
     }
 }
 
-async fn check_version_info(client: &reqwest::Client, config: &RdEngineConfig) -> CheckResult {
-    let url = config.api_url("/v1/settings");
+async fn check_version_info(client: &RdClient) -> CheckResult {
     // Note: timeout is already configured on the client via HttpClientConfig
-    match client
-        .get(&url)
-        .header("Authorization", config.basic_auth())
-        .send()
-        .await
-    {
-        Ok(response) => {
-            if response.status().is_success() {
-                match response.json::<serde_json::Value>().await {
-                    Ok(settings) => {
-                        let k8s_version = settings
-                            .get("kubernetes")
-                            .and_then(|k| k.get("version"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("unknown");
-
-                        let container_engine = settings
-                            .get("containerEngine")
-                            .and_then(|c| c.get("name"))
-                            .and_then(|n| n.as_str())
-                            .unwrap_or("unknown");
-
-                        CheckResult::ok(
-                            "Configuration",
-                            format!("k8s {k8s_version}, engine: {container_engine}"),
-                        )
-                    }
-                    Err(e) => CheckResult::warn("Configuration", "Could not parse settings")
-                        .with_details(e.to_string()),
-                }
-            } else {
-                CheckResult::warn("Configuration", format!("HTTP {}", response.status()))
-            }
+    match client.settings().await {
+        Ok(settings) => {
+            let k8s_version = settings
+                .get("kubernetes")
+                .and_then(|k| k.get("version"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown");
+
+            let container_engine = settings
+                .get("containerEngine")
+                .and_then(|c| c.get("name"))
+                .and_then(|n| n.as_str())
+                .unwrap_or("unknown");
+
+            CheckResult::ok(
+                "Configuration",
+                format!("k8s {k8s_version}, engine: {container_engine}"),
+            )
+        }
+        Err(RdClientError::Status { status, .. }) => {
+            CheckResult::warn("Configuration", format!("HTTP {status}"))
+        }
+        Err(e @ RdClientError::InvalidResponse { .. }) => {
+            CheckResult::warn("Configuration", "Could not parse settings")
+                .with_details(e.to_string())
         }
         Err(e) => CheckResult::fail("Configuration", "Request failed").with_details(e.to_string()),
     }
 }
 
+/// Report which deployment profile is in effect and what it locks
+async fn check_deployment_profile(
+    cli: &Cli,
+    rd_running: bool,
+    show_progress: bool,
+) -> Vec<CheckResult> {
+    let mut results = Vec::new();
+
+    if show_progress {
+        print_category_header("Deployment Profile");
+    }
+
+    let profile_check = profile_check(&locked::find_profiles());
+    if show_progress {
+        print_check_result(&profile_check);
+    }
+    results.push(profile_check);
+
+    // The API knows the locks actually applied, whatever their source
+    let locked_check = match RdClient::from_cli(cli) {
+        Ok(client) if rd_running => match client.locked_settings().await {
+            Ok(tree) => {
+                let paths = locked::LockedSettings::from_api(tree).paths();
+                if paths.is_empty() {
+                    CheckResult::ok("Locked Settings", "No settings are locked")
+                } else {
+                    let paths: Vec<String> = paths.iter().map(ToString::to_string).collect();
+                    CheckResult::ok(
+                        "Locked Settings",
+                        format!("{} setting(s) locked", paths.len()),
+                    )
+                    .with_details(paths.join("\n"))
+                }
+            }
+            Err(e) => CheckResult::skip("Locked Settings", "Not reported by this version")
+                .with_details(e.to_string()),
+        },
+        _ => CheckResult::skip("Locked Settings", "Skipped - Rancher Desktop not running"),
+    };
+    if show_progress {
+        print_check_result(&locked_check);
+        println!();
+    }
+    results.push(locked_check);
+
+    results
+}
+
+/// Describe the installed profiles, noting which ones Rancher Desktop uses
+fn profile_check(profiles: &[DeploymentProfile]) -> CheckResult {
+    if profiles.is_empty() {
+        return CheckResult::ok("Profile", "No deployment profile installed");
+    }
+
+    // System profiles take precedence; user profiles are then ignored
+    let system = profiles.iter().any(|p| p.scope == ProfileScope::System);
+    let details: Vec<String> = profiles
+        .iter()
+        .map(|p| {
+            let ignored = if system && p.scope == ProfileScope::User {
+                " (ignored)"
+            } else {
+                ""
+            };
+            format!("{:?} ({:?}): {}{ignored}", p.kind, p.scope, p.location).to_lowercase()
+        })
+        .collect();
+
+    let scope = if system { "System" } else { "User" };
+    CheckResult::ok("Profile", format!("{scope} deployment profile in effect"))
+        .with_details(details.join("\n"))
+}
+
 /// Check k3s cache status
 fn check_cache_status(show_progress: bool) -> Vec<CheckResult> {
     let mut results = Vec::new();
@@ -535,18 +606,32 @@ async fn check_network_connectivity(cli: &Cli, show_progress: bool) -> Vec<Check
         print_category_header("Network Connectivity");
     }
 
+    // An invalid mirror configuration is reported, and GitHub checked instead
+    let (releases, mirror_check) = match K3sReleases::from_cli(cli) {
+        Ok(releases) => (releases, None),
+        Err(e) => (
+            K3sReleases::GitHub,
+            Some(
+                CheckResult::fail("K3s Mirror", "Invalid mirror configuration")
+                    .with_details(format!("{e:#}")),
+            ),
+        ),
+    };
+    let endpoints = releases.endpoints();
+
     // Run HTTPS checks and DNS check concurrently for better performance
-    let https_futures: Vec<_> = REQUIRED_ENDPOINTS
+    let https_futures: Vec<_> = endpoints
         .iter()
         .map(|(name, url)| check_https_connectivity(name, url, cli))
         .collect();
 
-    let dns_future = check_dns_resolution("api.github.com");
+    let domain = releases.domain();
+    let dns_future = check_dns_resolution(&domain);
 
     let (https_results, dns_check) =
         tokio::join!(futures_util::future::join_all(https_futures), dns_future);
 
-    let mut results: Vec<CheckResult> = https_results;
+    let mut results: Vec<CheckResult> = mirror_check.into_iter().chain(https_results).collect();
 
     if show_progress {
         for result in &results {
@@ -785,6 +870,34 @@ mod tests {
         assert_ne!(CheckStatus::Ok, CheckStatus::Fail);
     }
 
+    #[test]
+    fn test_profile_check() {
+        use crate::commands::settings::locked::ProfileKind;
+
+        assert_eq!(
+            profile_check(&[]).message,
+            "No deployment profile installed"
+        );
+
+        let profiles = [
+            DeploymentProfile {
+                kind: ProfileKind::Locked,
+                scope: ProfileScope::System,
+                location: "/etc/rancher-desktop/locked.json".to_string(),
+            },
+            DeploymentProfile {
+                kind: ProfileKind::Defaults,
+                scope: ProfileScope::User,
+                location: "/home/me/.config/rancher-desktop.defaults.json".to_string(),
+            },
+        ];
+        let check = profile_check(&profiles);
+        assert_eq!(check.message, "System deployment profile in effect");
+        let details = check.details.unwrap();
+        assert!(details.contains("locked (system): /etc/rancher-desktop/locked.json"));
+        assert!(details.ends_with("(ignored)"));
+    }
+
     #[test]
     fn test_diagnose_summary_counts() {
         let summary = DiagnoseSummary {
@@ -3,5 +3,7 @@ pub mod backend;
 pub mod cache;
 pub mod certs;
 pub mod diagnose;
+pub mod profile;
 pub mod settings;
 pub mod version;
+pub mod wait;
@@ -0,0 +1,315 @@
+//! Settings profiles command.
+//!
+//! A profile is a named snapshot of Rancher Desktop settings kept under the
+//! ranch-hand config directory, so whole setups (e.g. "containerd with
+//! Kubernetes 1.30" and "moby without Kubernetes") can be switched by name.
+
+use crate::cli::Cli;
+use crate::client::rd::RdClient;
+use crate::commands::backend;
+use crate::commands::settings::{apply_document, print_restart_note, print_settings_tree};
+use crate::paths::ranch_hand_config_dir;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::info;
+
+/// Directory under the ranch-hand config dir that holds one JSON file per profile
+const PROFILES_DIR: &str = "profiles";
+
+/// A saved profile as listed by `rh profile list`
+#[derive(Debug, Serialize)]
+pub struct ProfileEntry {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Profiles stored as `<name>.json` files in a directory
+#[derive(Debug, Clone)]
+pub struct ProfileStore {
+    dir: PathBuf,
+}
+
+impl ProfileStore {
+    /// The profile store under the ranch-hand config directory
+    pub fn open() -> Result<Self> {
+        Ok(Self::with_dir(ranch_hand_config_dir()?.join(PROFILES_DIR)))
+    }
+
+    /// A profile store rooted at `dir`
+    #[must_use]
+    pub fn with_dir(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        validate_name(name)?;
+        Ok(self.dir.join(format!("{name}.json")))
+    }
+
+    /// Save `settings` as profile `name`, failing if it exists unless `overwrite`
+    pub fn save(&self, name: &str, settings: &Value, overwrite: bool) -> Result<PathBuf> {
+        let path = self.path(name)?;
+        if path.exists() && !overwrite {
+            anyhow::bail!("Profile '{name}' already exists. Use --force to overwrite it.");
+        }
+
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        fs::write(
+            &path,
+            format!("{}\n", serde_json::to_string_pretty(settings)?),
+        )
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        Ok(path)
+    }
+
+    /// Load profile `name`
+    pub fn load(&self, name: &str) -> Result<Value> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            anyhow::bail!("Profile '{name}' not found");
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse profile {}", path.display()))
+    }
+
+    /// Delete profile `name`
+    pub fn delete(&self, name: &str) -> Result<()> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            anyhow::bail!("Profile '{name}' not found");
+        }
+
+        fs::remove_file(&path).with_context(|| format!("Failed to delete {}", path.display()))
+    }
+
+    /// All saved profiles, sorted by name
+    pub fn list(&self) -> Result<Vec<ProfileEntry>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut profiles = Vec::new();
+        for entry in fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read {}", self.dir.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                profiles.push(ProfileEntry {
+                    name: name.to_string(),
+                    path: path.clone(),
+                });
+            }
+        }
+
+        profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(profiles)
+    }
+}
+
+/// Profile names become file names, so keep them to a safe character set
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+    if !valid {
+        anyhow::bail!(
+            "Invalid profile name '{name}': use letters, digits, '-', '_' and '.' (not leading)"
+        );
+    }
+
+    Ok(())
+}
+
+/// Save the current settings as profile `name`
+pub async fn save(cli: &Cli, name: &str, force: bool) -> Result<()> {
+    info!("Saving profile {}", name);
+
+    let store = ProfileStore::open()?;
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    let settings = client.settings().await?;
+    let path = store.save(name, &settings, force)?;
+
+    if cli.json {
+        let output = serde_json::json!({
+            "name": name,
+            "file": path,
+            "success": true
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !cli.quiet {
+        println!("{} profile {}", "Saved".green(), name.bold());
+    }
+
+    Ok(())
+}
+
+/// List saved profiles
+pub fn list(cli: &Cli) -> Result<()> {
+    let profiles = ProfileStore::open()?.list()?;
+
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&profiles)?);
+    } else if cli.quiet {
+        for profile in &profiles {
+            println!("{}", profile.name);
+        }
+    } else if profiles.is_empty() {
+        println!("No profiles saved. Create one with 'rh profile save <name>'.");
+    } else {
+        println!("{}", "Saved profiles".bold().cyan());
+        println!("{}", "=".repeat(40));
+        for profile in &profiles {
+            println!("  {}", profile.name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Show the settings saved in profile `name`
+pub fn show(cli: &Cli, name: &str) -> Result<()> {
+    let settings = ProfileStore::open()?.load(name)?;
+
+    if cli.json || cli.quiet {
+        println!("{}", serde_json::to_string_pretty(&settings)?);
+    } else {
+        println!("{} {}", "Profile".bold().cyan(), name.bold());
+        println!("{}", "=".repeat(40));
+        println!();
+        print_settings_tree(&settings, 0);
+    }
+
+    Ok(())
+}
+
+/// Apply profile `name`, or only validate it if `dry_run`.
+///
+/// With `restart`, the backend is restarted if the change requires it; with
+/// `--json` its final status is included in the single output document.
+pub async fn apply(
+    cli: &Cli,
+    name: &str,
+    dry_run: bool,
+    restart: bool,
+    wait_timeout: Duration,
+) -> Result<()> {
+    info!("Applying profile {}", name);
+
+    let settings = ProfileStore::open()?.load(name)?;
+
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    let Some(proposal) = apply_document(cli, &client, &settings, dry_run).await? else {
+        return Ok(());
+    };
+
+    let restarting = restart && proposal.requires_restart;
+
+    if cli.json {
+        let backend = if restarting {
+            Some(backend::restart_silently(cli, wait_timeout).await?)
+        } else {
+            None
+        };
+        let output = serde_json::json!({
+            "name": name,
+            "success": true,
+            "requiresRestart": proposal.requires_restart,
+            "restarting": restarting,
+            "backend": backend
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if !cli.quiet {
+        println!("{} profile {}", "Applied".green(), name.bold());
+        if !restarting {
+            print_restart_note(&proposal);
+        }
+    }
+
+    if restarting {
+        backend::restart(cli, true, wait_timeout).await?;
+    }
+
+    Ok(())
+}
+
+/// Delete profile `name`
+pub fn delete(cli: &Cli, name: &str) -> Result<()> {
+    ProfileStore::open()?.delete(name)?;
+
+    if cli.json {
+        let output = serde_json::json!({
+            "name": name,
+            "success": true
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !cli.quiet {
+        println!("{} profile {}", "Deleted".green(), name.bold());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ProfileStore::with_dir(dir.path().join("profiles"));
+        let settings = serde_json::json!({
+            "containerEngine": { "name": "moby" },
+            "kubernetes": { "enabled": false }
+        });
+
+        assert!(store.list().unwrap().is_empty());
+
+        let path = store.save("moby-no-k8s", &settings, false).unwrap();
+        assert_eq!(path, dir.path().join("profiles").join("moby-no-k8s.json"));
+        assert_eq!(store.load("moby-no-k8s").unwrap(), settings);
+
+        // Saving over an existing profile needs overwrite
+        assert!(store.save("moby-no-k8s", &settings, false).is_err());
+        store.save("moby-no-k8s", &settings, true).unwrap();
+
+        store.save("containerd-1.30", &settings, false).unwrap();
+        let names: Vec<String> = store.list().unwrap().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["containerd-1.30", "moby-no-k8s"]);
+
+        store.delete("moby-no-k8s").unwrap();
+        assert!(store.load("moby-no-k8s").is_err());
+        assert!(store.delete("moby-no-k8s").is_err());
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("work_k8s-1.30").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name(".hidden").is_err());
+        assert!(validate_name("../escape").is_err());
+        assert!(validate_name("has space").is_err());
+    }
+}
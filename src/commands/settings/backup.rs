@@ -0,0 +1,154 @@
+//! Settings backups taken before a factory reset.
+//!
+//! Each backup is a `settings-<timestamp>.json` file under the ranch-hand data
+//! directory, so a reset can be rolled back with `rh settings restore`.
+
+use crate::paths::ranch_hand_data_dir;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory under the ranch-hand data dir that holds settings backups
+const BACKUPS_DIR: &str = "backups";
+
+/// A saved settings backup
+#[derive(Debug, Clone, Serialize)]
+pub struct Backup {
+    pub name: String,
+    pub path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+}
+
+/// Returns the directory settings backups are written to
+pub fn backups_dir() -> Result<PathBuf> {
+    Ok(ranch_hand_data_dir()?.join(BACKUPS_DIR))
+}
+
+/// Write `settings` to a new timestamped backup in `dir`
+pub fn save_backup(dir: &Path, settings: &Value, now: DateTime<Local>) -> Result<Backup> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let stem = format!("settings-{}", now.format("%Y%m%d-%H%M%S"));
+    let mut name = stem.clone();
+    let mut suffix = 1;
+    while dir.join(format!("{name}.json")).exists() {
+        name = format!("{stem}-{suffix}");
+        suffix += 1;
+    }
+
+    let path = dir.join(format!("{name}.json"));
+    fs::write(
+        &path,
+        format!("{}\n", serde_json::to_string_pretty(settings)?),
+    )
+    .with_context(|| format!("Failed to write settings backup {}", path.display()))?;
+
+    Ok(Backup {
+        name,
+        path,
+        created: Some(now.format("%Y-%m-%d %H:%M:%S").to_string()),
+    })
+}
+
+/// All backups in `dir`, newest first
+pub fn list_backups(dir: &Path) -> Result<Vec<Backup>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+
+        let created = entry.metadata().and_then(|m| m.modified()).ok().map(|t| {
+            DateTime::<Local>::from(t)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        });
+
+        backups.push(Backup {
+            name: name.to_string(),
+            path: path.clone(),
+            created,
+        });
+    }
+
+    // Names embed the timestamp, so they sort chronologically
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    Ok(backups)
+}
+
+/// Resolve `backup` as a backup name in `dir`, or else as a file path
+pub fn resolve_backup(dir: &Path, backup: &str) -> Result<PathBuf> {
+    let named = dir.join(format!("{backup}.json"));
+    if !backup.contains(['/', '\\']) && named.exists() {
+        return Ok(named);
+    }
+
+    let path = PathBuf::from(backup);
+    if path.is_file() {
+        return Ok(path);
+    }
+
+    anyhow::bail!("Backup '{backup}' not found. Run 'rh settings backups list' to see backups.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_save_and_list_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = serde_json::json!({ "kubernetes": { "enabled": true } });
+        let now = Local.with_ymd_and_hms(2026, 10, 16, 9, 30, 0).unwrap();
+
+        assert!(list_backups(dir.path()).unwrap().is_empty());
+
+        let first = save_backup(dir.path(), &settings, now).unwrap();
+        assert_eq!(first.name, "settings-20261016-093000");
+        // A second backup in the same second does not overwrite the first
+        let second = save_backup(dir.path(), &settings, now).unwrap();
+        assert_eq!(second.name, "settings-20261016-093000-1");
+        let later = save_backup(dir.path(), &settings, now + chrono::Duration::hours(1)).unwrap();
+
+        let names: Vec<String> = list_backups(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|b| b.name)
+            .collect();
+        assert_eq!(names, [later.name, second.name, first.name.clone()]);
+
+        let content = fs::read_to_string(&first.path).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&content).unwrap(), settings);
+    }
+
+    #[test]
+    fn test_resolve_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = Local.with_ymd_and_hms(2026, 10, 16, 9, 30, 0).unwrap();
+        let backup = save_backup(dir.path(), &serde_json::json!({}), now).unwrap();
+
+        assert_eq!(
+            resolve_backup(dir.path(), &backup.name).unwrap(),
+            backup.path
+        );
+        assert_eq!(
+            resolve_backup(dir.path(), backup.path.to_str().unwrap()).unwrap(),
+            backup.path
+        );
+        assert!(resolve_backup(dir.path(), "settings-19700101-000000").is_err());
+    }
+}
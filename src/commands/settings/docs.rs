@@ -0,0 +1,406 @@
+//! Descriptions of known Rancher Desktop settings for `rh settings explain`.
+//!
+//! Rancher Desktop does not expose its settings schema over the API, so this
+//! table is maintained by hand from the Rancher Desktop documentation. Paths
+//! missing here are still usable with the other settings commands.
+
+use serde::Serialize;
+use SettingKind::{Boolean, Integer, Map, StringList};
+
+/// Operating systems a setting applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    Windows,
+    #[serde(rename = "macos")]
+    MacOs,
+    Linux,
+}
+
+impl std::fmt::Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Windows => write!(f, "Windows"),
+            Self::MacOs => write!(f, "macOS"),
+            Self::Linux => write!(f, "Linux"),
+        }
+    }
+}
+
+const ALL: &[Platform] = &[Platform::Windows, Platform::MacOs, Platform::Linux];
+const UNIX: &[Platform] = &[Platform::MacOs, Platform::Linux];
+const MACOS: &[Platform] = &[Platform::MacOs];
+const WINDOWS: &[Platform] = &[Platform::Windows];
+
+/// The JSON type of a setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SettingKind {
+    Boolean,
+    Integer,
+    String,
+    StringList,
+    /// An object whose keys are chosen by the user (e.g. WSL distribution names)
+    Map,
+}
+
+impl std::fmt::Display for SettingKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Boolean => write!(f, "boolean"),
+            Self::Integer => write!(f, "integer"),
+            Self::String => write!(f, "string"),
+            Self::StringList => write!(f, "list of strings"),
+            Self::Map => write!(f, "map"),
+        }
+    }
+}
+
+/// Documentation for one setting
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingDoc {
+    pub path: &'static str,
+    pub kind: SettingKind,
+    pub description: &'static str,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub allowed_values: &'static [&'static str],
+    /// Default value as JSON text, if there is a fixed default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<&'static str>,
+    pub requires_restart: bool,
+    pub platforms: &'static [Platform],
+}
+
+const fn doc(
+    path: &'static str,
+    kind: SettingKind,
+    description: &'static str,
+    default: Option<&'static str>,
+    requires_restart: bool,
+    platforms: &'static [Platform],
+) -> SettingDoc {
+    SettingDoc {
+        path,
+        kind,
+        description,
+        allowed_values: &[],
+        default,
+        requires_restart,
+        platforms,
+    }
+}
+
+const fn choice(
+    path: &'static str,
+    description: &'static str,
+    allowed_values: &'static [&'static str],
+    default: Option<&'static str>,
+    requires_restart: bool,
+    platforms: &'static [Platform],
+) -> SettingDoc {
+    SettingDoc {
+        path,
+        kind: SettingKind::String,
+        description,
+        allowed_values,
+        default,
+        requires_restart,
+        platforms,
+    }
+}
+
+/// Known settings, sorted by path
+pub static SETTINGS_DOCS: &[SettingDoc] = &[
+    doc(
+        "WSL.integrations",
+        Map,
+        "WSL distributions to integrate with, keyed by distribution name.",
+        None,
+        false,
+        WINDOWS,
+    ),
+    doc(
+        "application.adminAccess",
+        Boolean,
+        "Allow Rancher Desktop to use administrative access for networking and socket setup.",
+        Some("false"),
+        true,
+        UNIX,
+    ),
+    doc(
+        "application.autoStart",
+        Boolean,
+        "Start Rancher Desktop when you log in.",
+        Some("false"),
+        false,
+        ALL,
+    ),
+    doc(
+        "application.debug",
+        Boolean,
+        "Write debug-level messages to the Rancher Desktop logs.",
+        Some("false"),
+        false,
+        ALL,
+    ),
+    doc(
+        "application.hideNotificationIcon",
+        Boolean,
+        "Hide the Rancher Desktop icon in the notification area or menu bar.",
+        Some("false"),
+        false,
+        ALL,
+    ),
+    choice(
+        "application.pathManagementStrategy",
+        "How Rancher Desktop adds its tools to PATH: by editing shell rc files, or not at all.",
+        &["rcfiles", "manual"],
+        None,
+        false,
+        UNIX,
+    ),
+    doc(
+        "application.startInBackground",
+        Boolean,
+        "Start without opening the main window.",
+        Some("false"),
+        false,
+        ALL,
+    ),
+    doc(
+        "application.telemetry.enabled",
+        Boolean,
+        "Send anonymous usage statistics.",
+        Some("true"),
+        false,
+        ALL,
+    ),
+    doc(
+        "application.updater.enabled",
+        Boolean,
+        "Check for and download Rancher Desktop updates automatically.",
+        Some("true"),
+        false,
+        ALL,
+    ),
+    doc(
+        "application.window.quitOnClose",
+        Boolean,
+        "Quit the application when the main window is closed.",
+        Some("false"),
+        false,
+        ALL,
+    ),
+    doc(
+        "containerEngine.allowedImages.enabled",
+        Boolean,
+        "Only allow pulling images that match containerEngine.allowedImages.patterns.",
+        Some("false"),
+        false,
+        ALL,
+    ),
+    doc(
+        "containerEngine.allowedImages.patterns",
+        StringList,
+        "Image name patterns that may be pulled when allowed images are enabled.",
+        Some("[]"),
+        false,
+        ALL,
+    ),
+    choice(
+        "containerEngine.name",
+        "Container runtime: containerd (nerdctl) or moby (docker).",
+        &["containerd", "moby"],
+        Some("\"containerd\""),
+        true,
+        ALL,
+    ),
+    doc(
+        "diagnostics.mutedChecks",
+        Map,
+        "Diagnostic checks hidden from the Diagnostics page, keyed by check id.",
+        Some("{}"),
+        false,
+        ALL,
+    ),
+    doc(
+        "diagnostics.showMuted",
+        Boolean,
+        "Show muted diagnostic checks.",
+        Some("false"),
+        false,
+        ALL,
+    ),
+    doc(
+        "experimental.virtualMachine.proxy.enabled",
+        Boolean,
+        "Route VM traffic through the Windows proxy settings.",
+        Some("false"),
+        true,
+        WINDOWS,
+    ),
+    doc(
+        "images.namespace",
+        SettingKind::String,
+        "containerd namespace shown on the Images page.",
+        Some("\"k8s.io\""),
+        false,
+        ALL,
+    ),
+    doc(
+        "images.showAll",
+        Boolean,
+        "Show system images on the Images page.",
+        Some("true"),
+        false,
+        ALL,
+    ),
+    doc(
+        "kubernetes.enabled",
+        Boolean,
+        "Run a k3s Kubernetes cluster.",
+        Some("true"),
+        true,
+        ALL,
+    ),
+    doc(
+        "kubernetes.ingress.localhostOnly",
+        Boolean,
+        "Bind ingress ports to localhost only instead of all interfaces.",
+        Some("false"),
+        true,
+        WINDOWS,
+    ),
+    doc(
+        "kubernetes.options.flannel",
+        Boolean,
+        "Use flannel for cluster networking; disable to install your own CNI.",
+        Some("true"),
+        true,
+        ALL,
+    ),
+    doc(
+        "kubernetes.options.traefik",
+        Boolean,
+        "Install the Traefik ingress controller.",
+        Some("true"),
+        true,
+        ALL,
+    ),
+    doc(
+        "kubernetes.port",
+        Integer,
+        "Port the Kubernetes API server listens on.",
+        Some("6443"),
+        true,
+        ALL,
+    ),
+    doc(
+        "kubernetes.version",
+        SettingKind::String,
+        "k3s Kubernetes version to run, without the leading v (e.g. 1.33.3).",
+        None,
+        true,
+        ALL,
+    ),
+    doc(
+        "portForwarding.includeKubernetesServices",
+        Boolean,
+        "List Kubernetes system services on the Port Forwarding page.",
+        Some("false"),
+        false,
+        ALL,
+    ),
+    doc(
+        "virtualMachine.memoryInGB",
+        Integer,
+        "Memory allocated to the VM, in GB.",
+        None,
+        true,
+        UNIX,
+    ),
+    choice(
+        "virtualMachine.mount.type",
+        "How host directories are shared with the VM.",
+        &["reverse-sshfs", "9p", "virtiofs"],
+        Some("\"reverse-sshfs\""),
+        true,
+        UNIX,
+    ),
+    doc(
+        "virtualMachine.numberCPUs",
+        Integer,
+        "Number of CPUs allocated to the VM.",
+        Some("2"),
+        true,
+        UNIX,
+    ),
+    choice(
+        "virtualMachine.type",
+        "Virtualization framework: QEMU, or Apple Virtualization.framework (VZ).",
+        &["qemu", "vz"],
+        None,
+        true,
+        MACOS,
+    ),
+    doc(
+        "virtualMachine.useRosetta",
+        Boolean,
+        "Use Rosetta to run amd64 containers on Apple silicon (requires the VZ VM type).",
+        Some("false"),
+        true,
+        MACOS,
+    ),
+];
+
+/// Look up the documentation for `path`
+#[must_use]
+pub fn find(path: &str) -> Option<&'static SettingDoc> {
+    SETTINGS_DOCS.iter().find(|doc| doc.path == path)
+}
+
+/// All documented paths, for shell completion
+pub fn paths() -> impl Iterator<Item = &'static str> {
+    SETTINGS_DOCS.iter().map(|doc| doc.path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::settings::SettingPath;
+
+    #[test]
+    fn test_settings_docs_are_valid() {
+        for pair in SETTINGS_DOCS.windows(2) {
+            assert!(
+                pair[0].path < pair[1].path,
+                "{} is out of order",
+                pair[1].path
+            );
+        }
+
+        for doc in SETTINGS_DOCS {
+            assert!(doc.path.parse::<SettingPath>().is_ok(), "{}", doc.path);
+            assert!(!doc.platforms.is_empty(), "{}", doc.path);
+            if let Some(default) = doc.default {
+                let value: serde_json::Value = serde_json::from_str(default).unwrap();
+                if let Some(s) = value.as_str() {
+                    assert!(
+                        doc.allowed_values.is_empty() || doc.allowed_values.contains(&s),
+                        "{}",
+                        doc.path
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_find() {
+        let doc = find("containerEngine.name").unwrap();
+        assert_eq!(doc.allowed_values, ["containerd", "moby"]);
+        assert!(doc.requires_restart);
+        assert!(find("kubernetes.nonexistent").is_none());
+    }
+}
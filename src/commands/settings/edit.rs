@@ -0,0 +1,206 @@
+//! Interactive settings editor, in the style of `kubectl edit`.
+//!
+//! The current settings are written to a temporary file and opened in
+//! `$VISUAL` or `$EDITOR`. The edited document is validated with
+//! `propose_settings` and its diff shown; on errors the editor is re-opened
+//! with the edited content, and valid changes are applied after confirmation.
+//! Changes to settings locked by a deployment profile are refused the same way.
+
+use super::{
+    diff_settings, locked, merge_settings, parse_settings, print_diff, print_restart_note,
+    serialize_settings,
+};
+use crate::cli::{Cli, SettingsFormat};
+use crate::client::rd::RdClient;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use dialoguer::Confirm;
+use std::fs;
+use std::io::IsTerminal;
+use std::process::Command;
+use tempfile::NamedTempFile;
+use tracing::{debug, info};
+
+/// Edit settings in `$EDITOR` and apply them after validation and confirmation
+pub async fn edit(cli: &Cli, format: SettingsFormat) -> Result<()> {
+    info!("Editing settings");
+
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "rh settings edit needs an interactive terminal.\n\
+             Use 'rh settings export' and 'rh settings import' in scripts."
+        );
+    }
+
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    let current = client.settings().await?;
+    let locked = locked::fetch(&client).await;
+    let original = serialize_settings(&current, format)?;
+
+    let file = temp_file(format)?;
+    let mut document = original.clone();
+
+    loop {
+        fs::write(file.path(), &document)
+            .with_context(|| format!("Failed to write {}", file.path().display()))?;
+        run_editor(file.path())?;
+        document = fs::read_to_string(file.path())
+            .with_context(|| format!("Failed to read {}", file.path().display()))?;
+
+        if document == original {
+            println!("{}", "Edit cancelled, no changes made.".yellow());
+            return Ok(());
+        }
+
+        let edited = match parse_settings(&document, format) {
+            Ok(edited) => edited,
+            Err(e) => {
+                eprintln!("{} {e:#}", "Error:".red().bold());
+                if confirm("Edit again?", true)? {
+                    continue;
+                }
+                anyhow::bail!("Edited settings could not be parsed");
+            }
+        };
+
+        // Rancher Desktop merges the document into the current settings
+        let changes = diff_settings(&current, &merge_settings(&current, &edited));
+        if changes.is_empty() {
+            println!("{}", "No changes to apply.".green());
+            return Ok(());
+        }
+
+        println!();
+        print_diff(&changes);
+
+        if let Err(e) = locked.check(&changes) {
+            eprintln!();
+            eprintln!("{} {e:#}", "Error:".red().bold());
+            if confirm("Edit again?", true)? {
+                continue;
+            }
+            return Err(e);
+        }
+
+        let proposal = client.propose_settings(&edited).await?;
+        if proposal.has_errors() {
+            eprintln!();
+            eprintln!(
+                "{} {}",
+                "Invalid settings:".red().bold(),
+                serde_json::to_string_pretty(&proposal.errors)?
+            );
+            if confirm("Edit again?", true)? {
+                continue;
+            }
+            anyhow::bail!("Edited settings are invalid");
+        }
+
+        if proposal.requires_restart {
+            println!();
+            println!(
+                "{} Applying these changes requires a restart.",
+                "Note:".yellow().bold()
+            );
+        }
+
+        println!();
+        if !confirm("Apply these changes?", true)? {
+            println!("{}", "Edit cancelled, no changes made.".yellow());
+            return Ok(());
+        }
+
+        client.put_settings(&edited).await?;
+
+        if cli.json {
+            let output = serde_json::json!({
+                "changes": changes,
+                "requiresRestart": proposal.requires_restart,
+                "success": true
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else if !cli.quiet {
+            println!("{} {} setting(s)", "Applied".green(), changes.len());
+            print_restart_note(&proposal);
+        }
+
+        return Ok(());
+    }
+}
+
+fn confirm(prompt: &str, default: bool) -> Result<bool> {
+    Confirm::new()
+        .with_prompt(prompt)
+        .default(default)
+        .interact()
+        .context("Failed to get confirmation")
+}
+
+/// Open `path` in the user's editor and wait for it to exit
+fn run_editor(path: &std::path::Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| default_editor().to_string());
+    let (program, args) = split_editor(&editor).context("$EDITOR is empty")?;
+    debug!("Running editor: {} {:?}", program, args);
+
+    let status = Command::new(program)
+        .args(args)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to run editor: {editor}"))?;
+
+    if !status.success() {
+        anyhow::bail!("Editor exited with {status}");
+    }
+
+    Ok(())
+}
+
+fn default_editor() -> &'static str {
+    if cfg!(windows) {
+        "notepad"
+    } else {
+        "vi"
+    }
+}
+
+/// Split an editor command such as `code --wait` into program and arguments
+fn split_editor(editor: &str) -> Option<(&str, Vec<&str>)> {
+    let mut parts = editor.split_whitespace();
+    let program = parts.next()?;
+    Some((program, parts.collect()))
+}
+
+/// Create the temporary settings file, removed when dropped.
+///
+/// The name is random and the file is created exclusively, readable only by
+/// the user, since it holds the full settings.
+fn temp_file(format: SettingsFormat) -> Result<NamedTempFile> {
+    let suffix = match format {
+        SettingsFormat::Json => ".json",
+        SettingsFormat::Yaml => ".yaml",
+    };
+    tempfile::Builder::new()
+        .prefix("rh-settings-")
+        .suffix(suffix)
+        .tempfile()
+        .context("Failed to create a temporary settings file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_editor() {
+        assert_eq!(split_editor("vim"), Some(("vim", vec![])));
+        assert_eq!(
+            split_editor("code --wait --new-window"),
+            Some(("code", vec!["--wait", "--new-window"]))
+        );
+        assert_eq!(split_editor("   "), None);
+    }
+}
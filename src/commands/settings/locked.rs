@@ -0,0 +1,382 @@
+//! Settings locked by a Rancher Desktop deployment profile.
+//!
+//! Administrators can install a "locked" deployment profile whose settings
+//! users cannot change. Rancher Desktop reports them at `/v1/settings/locked`;
+//! older versions without that endpoint fall back to the JSON profile files
+//! on disk. Edits touching a locked setting are refused before proposing.
+
+use super::path::{PathSegment, SettingPath};
+use super::SettingChange;
+use crate::client::rd::RdClient;
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::debug;
+
+/// Whether a profile supplies defaults or locks settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileKind {
+    Defaults,
+    Locked,
+}
+
+/// Whether a profile was installed for all users or only the current one.
+///
+/// Rancher Desktop ignores user profiles when any system profile exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileScope {
+    System,
+    User,
+}
+
+/// A deployment profile found on this machine
+#[derive(Debug, Clone, Serialize)]
+pub struct DeploymentProfile {
+    pub kind: ProfileKind,
+    pub scope: ProfileScope,
+    /// File path, or registry key on Windows
+    pub location: String,
+}
+
+/// The set of locked settings, as a tree mirroring the settings document
+/// with `true` at each locked setting
+#[derive(Debug, Clone, Default)]
+pub struct LockedSettings {
+    tree: Value,
+}
+
+impl LockedSettings {
+    /// Wrap the document returned by `/v1/settings/locked`
+    #[must_use]
+    pub fn from_api(tree: Value) -> Self {
+        Self { tree }
+    }
+
+    /// Build from a locked profile file, where every value present is locked
+    #[must_use]
+    pub fn from_profile(profile: &Value) -> Self {
+        let mut tree = mark_locked(profile);
+        // The profile format version is not a setting
+        if let Value::Object(obj) = &mut tree {
+            obj.remove("version");
+        }
+        Self { tree }
+    }
+
+    /// Whether changing the value at `path` would change a locked setting
+    #[must_use]
+    pub fn is_locked(&self, path: &SettingPath) -> bool {
+        let mut node = &self.tree;
+        for segment in path.segments() {
+            match (node, segment) {
+                (Value::Bool(true), _) => return true,
+                (Value::Object(obj), PathSegment::Key(key)) => match obj.get(key) {
+                    Some(child) => node = child,
+                    None => return false,
+                },
+                _ => return false,
+            }
+        }
+        // Replacing an object replaces every locked setting inside it
+        contains_locked(node)
+    }
+
+    /// Every locked setting path
+    #[must_use]
+    pub fn paths(&self) -> Vec<SettingPath> {
+        let mut paths = Vec::new();
+        collect_locked(&self.tree, &SettingPath::default(), &mut paths);
+        paths
+    }
+
+    /// Fail if any of `changes` touches a locked setting
+    pub fn check(&self, changes: &[SettingChange]) -> Result<()> {
+        let locked: Vec<&str> = changes
+            .iter()
+            .map(SettingChange::path)
+            .filter(|path| path.parse().is_ok_and(|path| self.is_locked(&path)))
+            .collect();
+
+        if !locked.is_empty() {
+            anyhow::bail!(
+                "Locked by a deployment profile and cannot be changed: {}\n\
+                 Run 'rh diagnose' to see which profile is in effect.",
+                locked.join(", ")
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Fetch the locked settings, falling back to the locked profile on disk.
+///
+/// Never fails: with no information available nothing is treated as locked,
+/// and Rancher Desktop still rejects the change when it is proposed.
+pub async fn fetch(client: &RdClient) -> LockedSettings {
+    match client.locked_settings().await {
+        Ok(tree) => LockedSettings::from_api(tree),
+        Err(e) => {
+            debug!("Could not fetch locked settings from the API: {}", e);
+            read_locked_profile().unwrap_or_default()
+        }
+    }
+}
+
+/// Read the locked profile in effect, if it is a JSON file
+fn read_locked_profile() -> Option<LockedSettings> {
+    let profile = effective_profiles()
+        .into_iter()
+        .find(|p| p.kind == ProfileKind::Locked)?;
+    // macOS profiles are plists and Windows profiles live in the registry
+    let is_json = std::path::Path::new(&profile.location)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    if !is_json {
+        return None;
+    }
+
+    let content = std::fs::read_to_string(&profile.location).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(value) => Some(LockedSettings::from_profile(&value)),
+        Err(e) => {
+            debug!("Could not parse {}: {}", profile.location, e);
+            None
+        }
+    }
+}
+
+/// The deployment profiles Rancher Desktop uses: the system profiles if any
+/// are installed, otherwise the user profiles
+#[must_use]
+pub fn effective_profiles() -> Vec<DeploymentProfile> {
+    let profiles = find_profiles();
+    let system = profiles.iter().any(|p| p.scope == ProfileScope::System);
+    profiles
+        .into_iter()
+        .filter(|p| !system || p.scope == ProfileScope::System)
+        .collect()
+}
+
+/// All deployment profiles installed on this machine
+#[must_use]
+pub fn find_profiles() -> Vec<DeploymentProfile> {
+    candidates()
+        .into_iter()
+        .filter(|(_, _, location)| profile_exists(location))
+        .map(|(kind, scope, location)| DeploymentProfile {
+            kind,
+            scope,
+            location,
+        })
+        .collect()
+}
+
+/// Profile locations Rancher Desktop reads, in precedence order
+#[cfg(target_os = "linux")]
+fn candidates() -> Vec<(ProfileKind, ProfileScope, String)> {
+    let mut candidates = vec![
+        (
+            ProfileKind::Defaults,
+            ProfileScope::System,
+            "/etc/rancher-desktop/defaults.json".to_string(),
+        ),
+        (
+            ProfileKind::Locked,
+            ProfileScope::System,
+            "/etc/rancher-desktop/locked.json".to_string(),
+        ),
+    ];
+    if let Some(config) = dirs::config_dir() {
+        for (kind, file) in [
+            (ProfileKind::Defaults, "rancher-desktop.defaults.json"),
+            (ProfileKind::Locked, "rancher-desktop.locked.json"),
+        ] {
+            candidates.push((
+                kind,
+                ProfileScope::User,
+                config.join(file).to_string_lossy().into_owned(),
+            ));
+        }
+    }
+    candidates
+}
+
+/// Profile locations Rancher Desktop reads, in precedence order
+#[cfg(target_os = "macos")]
+fn candidates() -> Vec<(ProfileKind, ProfileScope, String)> {
+    let mut candidates = Vec::new();
+    for dir in ["/Library/Managed Preferences", "/Library/Preferences"] {
+        for (kind, file) in [
+            (
+                ProfileKind::Defaults,
+                "io.rancherdesktop.profile.defaults.plist",
+            ),
+            (
+                ProfileKind::Locked,
+                "io.rancherdesktop.profile.locked.plist",
+            ),
+        ] {
+            candidates.push((kind, ProfileScope::System, format!("{dir}/{file}")));
+        }
+    }
+    if let Some(home) = dirs::home_dir() {
+        let dir = home.join("Library/Preferences");
+        for (kind, file) in [
+            (
+                ProfileKind::Defaults,
+                "io.rancherdesktop.profile.defaults.plist",
+            ),
+            (
+                ProfileKind::Locked,
+                "io.rancherdesktop.profile.locked.plist",
+            ),
+        ] {
+            candidates.push((
+                kind,
+                ProfileScope::User,
+                dir.join(file).to_string_lossy().into_owned(),
+            ));
+        }
+    }
+    candidates
+}
+
+/// Profile registry keys Rancher Desktop reads, in precedence order
+#[cfg(target_os = "windows")]
+fn candidates() -> Vec<(ProfileKind, ProfileScope, String)> {
+    let mut candidates = Vec::new();
+    for (scope, hive) in [(ProfileScope::System, "HKLM"), (ProfileScope::User, "HKCU")] {
+        for (kind, key) in [
+            (ProfileKind::Defaults, "Defaults"),
+            (ProfileKind::Locked, "Locked"),
+        ] {
+            candidates.push((
+                kind,
+                scope,
+                format!(r"{hive}\SOFTWARE\Policies\Rancher Desktop\{key}"),
+            ));
+        }
+    }
+    candidates
+}
+
+#[cfg(not(target_os = "windows"))]
+fn profile_exists(location: &str) -> bool {
+    std::path::Path::new(location).is_file()
+}
+
+#[cfg(target_os = "windows")]
+fn profile_exists(location: &str) -> bool {
+    std::process::Command::new("reg")
+        .args(["query", location])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Replace every leaf of a profile document with `true`
+fn mark_locked(value: &Value) -> Value {
+    match value {
+        Value::Object(obj) => Value::Object(
+            obj.iter()
+                .map(|(key, child)| (key.clone(), mark_locked(child)))
+                .collect(),
+        ),
+        _ => Value::Bool(true),
+    }
+}
+
+fn contains_locked(value: &Value) -> bool {
+    match value {
+        Value::Bool(locked) => *locked,
+        Value::Object(obj) => obj.values().any(contains_locked),
+        _ => false,
+    }
+}
+
+fn collect_locked(value: &Value, parent: &SettingPath, paths: &mut Vec<SettingPath>) {
+    match value {
+        Value::Bool(true) => paths.push(parent.clone()),
+        Value::Object(obj) => {
+            for (key, child) in obj {
+                collect_locked(child, &parent.join(PathSegment::Key(key.clone())), paths);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn path(s: &str) -> SettingPath {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_is_locked() {
+        let locked = LockedSettings::from_api(json!({
+            "containerEngine": {
+                "allowedImages": { "enabled": true, "patterns": true },
+                "name": false
+            },
+            "kubernetes": { "version": true }
+        }));
+
+        assert!(locked.is_locked(&path("kubernetes.version")));
+        assert!(locked.is_locked(&path("containerEngine.allowedImages.patterns[0]")));
+        assert!(locked.is_locked(&path("containerEngine.allowedImages.patterns[]")));
+        // Replacing a parent object would replace the locked settings in it
+        assert!(locked.is_locked(&path("containerEngine.allowedImages")));
+        assert!(!locked.is_locked(&path("containerEngine.name")));
+        assert!(!locked.is_locked(&path("kubernetes.enabled")));
+        assert!(!locked.is_locked(&path("virtualMachine.memoryInGB")));
+
+        let paths: Vec<String> = locked.paths().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            paths,
+            [
+                "containerEngine.allowedImages.enabled",
+                "containerEngine.allowedImages.patterns",
+                "kubernetes.version"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_profile() {
+        let locked = LockedSettings::from_profile(&json!({
+            "version": 10,
+            "containerEngine": { "allowedImages": { "enabled": false, "patterns": ["docker.io"] } }
+        }));
+
+        // A locked value of false is still locked
+        assert!(locked.is_locked(&path("containerEngine.allowedImages.enabled")));
+        assert!(locked.is_locked(&path("containerEngine.allowedImages.patterns")));
+        assert!(!locked.is_locked(&path("version")));
+    }
+
+    #[test]
+    fn test_check() {
+        let locked = LockedSettings::from_api(json!({ "kubernetes": { "version": true } }));
+
+        let allowed = [SettingChange::Changed {
+            path: "kubernetes.enabled".to_string(),
+            from: json!(true),
+            to: json!(false),
+        }];
+        assert!(locked.check(&allowed).is_ok());
+
+        let refused = [SettingChange::Changed {
+            path: "kubernetes.version".to_string(),
+            from: json!("1.29.0"),
+            to: json!("1.30.0"),
+        }];
+        let err = locked.check(&refused).unwrap_err().to_string();
+        assert!(err.contains("kubernetes.version"), "{err}");
+    }
+}
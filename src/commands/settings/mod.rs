@@ -0,0 +1,1509 @@
+//! Settings command for viewing and modifying Rancher Desktop settings.
+//!
+//! Supports viewing all settings, getting specific values using dot notation,
+//! setting values, removing array elements, editing list settings, editing all
+//! settings in `$EDITOR`, explaining documented settings, exporting, importing
+//! and diffing settings files, and factory reset with automatic backups. Paths
+//! use the grammar in [`path`]; settings locked by a deployment profile are
+//! marked and refused (see [`locked`]).
+
+mod backup;
+pub mod docs;
+mod edit;
+pub mod locked;
+mod path;
+mod schema;
+
+pub use backup::Backup;
+pub use edit::edit;
+pub use path::{PathSegment, SettingPath, SettingPathError};
+
+use crate::cli::{Cli, SettingsFormat};
+use crate::client::rd::{RdClient, SettingsProposal};
+use anyhow::{Context, Result};
+use chrono::Local;
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use tracing::{debug, info};
+
+/// Show all settings
+pub async fn show_all(cli: &Cli) -> Result<()> {
+    info!("Fetching all settings");
+
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    let settings = client.settings().await?;
+
+    if cli.json || cli.quiet {
+        println!("{}", serde_json::to_string_pretty(&settings)?);
+    } else {
+        let locked = locked::fetch(&client).await;
+
+        println!("{}", "Rancher Desktop Settings".bold().cyan());
+        println!("{}", "=".repeat(40));
+        println!();
+        print_tree(&settings, &SettingPath::default(), &locked, 0);
+
+        let count = locked.paths().len();
+        if count > 0 {
+            println!();
+            println!(
+                "{} {} setting(s) locked by a deployment profile",
+                "[locked]".red(),
+                count
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Get a specific setting value
+pub async fn get(cli: &Cli, path: &str) -> Result<()> {
+    info!("Getting setting: {}", path);
+
+    let path = parse_path(path)?;
+
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    let settings = client.settings().await?;
+
+    // Navigate to the requested path
+    let value = path
+        .get(&settings)
+        .ok_or_else(|| schema::not_found(&settings, &path))?;
+
+    if cli.json || cli.quiet {
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else {
+        println!("{}: {}", path.to_string().bold(), format_value(value));
+    }
+
+    Ok(())
+}
+
+/// Output structure for `rh settings explain <path>`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainOutput {
+    #[serde(flatten)]
+    pub doc: &'static docs::SettingDoc,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<Value>,
+}
+
+/// Describe a documented setting, or list all documented settings
+pub async fn explain(cli: &Cli, path: Option<&str>) -> Result<()> {
+    let Some(path) = path else {
+        if cli.json {
+            println!("{}", serde_json::to_string_pretty(docs::SETTINGS_DOCS)?);
+        } else {
+            for doc in docs::SETTINGS_DOCS {
+                println!("{:<44} {}", doc.path.bold(), doc.description);
+            }
+        }
+        return Ok(());
+    };
+
+    let doc = docs::find(path).with_context(|| format!("No documentation for setting: {path}"))?;
+
+    // The current value is a bonus; explain works without Rancher Desktop running
+    let current = match RdClient::from_cli(cli) {
+        Ok(client) => client.settings().await.ok(),
+        Err(_) => None,
+    }
+    .and_then(|settings| parse_path(path).ok()?.get(&settings).cloned());
+
+    if cli.json {
+        let output = ExplainOutput { doc, current };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("{}", doc.path.bold().cyan());
+    println!("  {}", doc.description);
+    println!();
+    println!("  {:<18} {}", "Type:", doc.kind);
+    if !doc.allowed_values.is_empty() {
+        println!(
+            "  {:<18} {}",
+            "Allowed values:",
+            doc.allowed_values.join(", ")
+        );
+    }
+    if let Some(default) = doc.default {
+        println!("  {:<18} {}", "Default:", default);
+    }
+    println!(
+        "  {:<18} {}",
+        "Requires restart:",
+        if doc.requires_restart { "yes" } else { "no" }
+    );
+    let platforms: Vec<String> = doc.platforms.iter().map(ToString::to_string).collect();
+    println!("  {:<18} {}", "Platforms:", platforms.join(", "));
+    if let Some(current) = &current {
+        println!("  {:<18} {}", "Current value:", format_diff_value(current));
+    }
+
+    Ok(())
+}
+
+/// A single `PATH=VALUE` edit for `rh settings set`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Assignment {
+    pub path: SettingPath,
+    pub value: Value,
+}
+
+/// A `PATH=VALUE` edit as typed, before the value is converted to the
+/// setting's type
+#[derive(Debug, Clone, PartialEq)]
+struct RawAssignment {
+    path: SettingPath,
+    value: String,
+}
+
+/// Set one or more setting values with a single propose and update, or only
+/// validate them if `dry_run`.
+///
+/// Values are converted to the type of the current setting. Paths that do not
+/// exist yet are rejected unless `allow_new`.
+pub async fn set(
+    cli: &Cli,
+    args: &[String],
+    from_stdin: bool,
+    allow_new: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let mut edits = parse_assignment_args(args)?;
+    if from_stdin {
+        let input =
+            std::io::read_to_string(std::io::stdin()).context("Failed to read from stdin")?;
+        edits.extend(parse_assignment_lines(&input)?);
+    }
+    if edits.is_empty() {
+        anyhow::bail!("No settings to set");
+    }
+
+    for edit in &edits {
+        info!("Setting {} = {}", edit.path, edit.value);
+    }
+
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    // Get current settings
+    let current = client.settings().await?;
+    let mut settings = current.clone();
+
+    // Apply every edit to the same document so there is one round trip
+    let mut assignments = Vec::with_capacity(edits.len());
+    for RawAssignment { path, value } in edits {
+        let value = coerce_assignment(&settings, &path, &value, allow_new)?;
+        path.set(&mut settings, value.clone())
+            .with_context(|| format!("Failed to set value at path: {path}"))?;
+        assignments.push(Assignment { path, value });
+    }
+
+    let Some(proposal) = apply_settings(cli, &client, &current, &settings, dry_run).await? else {
+        return Ok(());
+    };
+
+    if cli.json {
+        let output = match assignments.as_slice() {
+            [Assignment { path, value }] => serde_json::json!({
+                "path": path,
+                "value": value,
+                "success": true
+            }),
+            _ => serde_json::json!({
+                "settings": assignments,
+                "success": true
+            }),
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !cli.quiet {
+        for Assignment { path, value } in &assignments {
+            println!(
+                "{} {} = {}",
+                "Set".green(),
+                path.to_string().bold(),
+                format_value(value)
+            );
+        }
+
+        print_restart_note(&proposal);
+    }
+
+    Ok(())
+}
+
+/// Convert `raw` to the type of the setting at `path` in `settings`
+fn coerce_assignment(
+    settings: &Value,
+    path: &SettingPath,
+    raw: &str,
+    allow_new: bool,
+) -> Result<Value> {
+    let expected = schema::expected_value(settings, path);
+    let is_new_key = expected.is_none()
+        && matches!(path.segments().last(), Some(PathSegment::Key(_)))
+        && path.get(settings).is_none();
+    if is_new_key && !allow_new {
+        return Err(schema::not_found(settings, path)
+            .context("Use --allow-new to create a setting that does not exist yet"));
+    }
+
+    let value =
+        schema::coerce_value(raw, expected).with_context(|| format!("Invalid value for {path}"))?;
+    debug!("Parsed value for {}: {:?}", path, value);
+    Ok(value)
+}
+
+/// Parse `set` arguments: either `PATH VALUE` or any number of `PATH=VALUE`
+fn parse_assignment_args(args: &[String]) -> Result<Vec<RawAssignment>> {
+    if let [path, value] = args {
+        if split_assignment(path).is_none() {
+            return Ok(vec![assignment(path, value)?]);
+        }
+    }
+
+    args.iter().map(|arg| parse_assignment(arg)).collect()
+}
+
+/// Parse `PATH=VALUE` lines, skipping blank lines and `#` comments
+fn parse_assignment_lines(input: &str) -> Result<Vec<RawAssignment>> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(parse_assignment)
+        .collect()
+}
+
+fn parse_assignment(arg: &str) -> Result<RawAssignment> {
+    let (path, value) =
+        split_assignment(arg).with_context(|| format!("Expected PATH=VALUE, got: {arg}"))?;
+    assignment(path.trim(), value.trim())
+}
+
+/// Split `PATH=VALUE` at the first `=` that is not inside a quoted key
+fn split_assignment(arg: &str) -> Option<(&str, &str)> {
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (i, c) in arg.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            '=' if !in_quotes => return Some((&arg[..i], &arg[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn assignment(path: &str, value: &str) -> Result<RawAssignment> {
+    Ok(RawAssignment {
+        path: parse_path(path)?,
+        value: value.to_string(),
+    })
+}
+
+fn parse_path(path: &str) -> Result<SettingPath> {
+    path.parse()
+        .with_context(|| format!("Invalid setting path: {path}"))
+}
+
+/// Remove the array element at `path`, or only validate the change if `dry_run`.
+///
+/// Rancher Desktop merges every update into the current settings, so an object
+/// key left out of an update is kept; only array elements can be removed.
+pub async fn unset(cli: &Cli, path: &str, dry_run: bool) -> Result<()> {
+    info!("Unsetting {}", path);
+
+    let path = parse_path(path)?;
+
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    let current = client.settings().await?;
+    if path.get(&current).is_none() {
+        return Err(schema::not_found(&current, &path));
+    }
+    let mut settings = current.clone();
+    let removed = path.remove(&mut settings)?;
+
+    // Arrays are replaced whole by an update, but a key missing from an
+    // object is kept, so removing one would silently do nothing
+    if merge_settings(&current, &settings) != settings {
+        anyhow::bail!(
+            "Cannot unset {path}: only array elements can be removed, because Rancher Desktop keeps object keys that are missing from an update. Set a new value instead."
+        );
+    }
+
+    let Some(proposal) = apply_settings(cli, &client, &current, &settings, dry_run).await? else {
+        return Ok(());
+    };
+
+    if cli.json {
+        let output = serde_json::json!({
+            "path": path,
+            "removed": removed,
+            "success": true
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !cli.quiet {
+        println!(
+            "{} {} (was {})",
+            "Unset".green(),
+            path.to_string().bold(),
+            format_diff_value(&removed)
+        );
+        print_restart_note(&proposal);
+    }
+
+    Ok(())
+}
+
+/// Append `value` to the list setting at `path` unless it is already present
+pub async fn add(cli: &Cli, path: &str, value: &str, dry_run: bool) -> Result<()> {
+    info!("Adding {} to {}", value, path);
+
+    let path = parse_path(path)?;
+
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    let current = client.settings().await?;
+    let mut settings = current.clone();
+
+    // Match the type of the list's existing elements
+    let expected = schema::expected_value(&current, &path.join(PathSegment::Append));
+    let value = schema::coerce_value(value, expected)
+        .with_context(|| format!("Invalid value for {path}"))?;
+    let added = add_to_list(&mut settings, &path, &value)?;
+
+    let proposal = if added {
+        match apply_settings(cli, &client, &current, &settings, dry_run).await? {
+            Some(proposal) => Some(proposal),
+            None => return Ok(()),
+        }
+    } else {
+        None
+    };
+
+    if cli.json {
+        let output = serde_json::json!({
+            "path": path,
+            "value": value,
+            "added": added,
+            "success": true
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !cli.quiet {
+        if added {
+            println!(
+                "{} {} to {}",
+                "Added".green(),
+                format_diff_value(&value),
+                path.to_string().bold()
+            );
+        } else {
+            println!(
+                "{} already contains {}",
+                path.to_string().bold(),
+                format_diff_value(&value)
+            );
+        }
+        if let Some(proposal) = &proposal {
+            print_restart_note(proposal);
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove every occurrence of `value` from the list setting at `path`
+pub async fn remove(cli: &Cli, path: &str, value: &str, dry_run: bool) -> Result<()> {
+    info!("Removing {} from {}", value, path);
+
+    let path = parse_path(path)?;
+
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    let current = client.settings().await?;
+    let mut settings = current.clone();
+
+    // Match the type of the list's existing elements
+    let expected = schema::expected_value(&current, &path.join(PathSegment::Append));
+    let value = schema::coerce_value(value, expected)
+        .with_context(|| format!("Invalid value for {path}"))?;
+    let removed = remove_from_list(&mut settings, &path, &value)?;
+
+    let proposal = if removed > 0 {
+        match apply_settings(cli, &client, &current, &settings, dry_run).await? {
+            Some(proposal) => Some(proposal),
+            None => return Ok(()),
+        }
+    } else {
+        None
+    };
+
+    if cli.json {
+        let output = serde_json::json!({
+            "path": path,
+            "value": value,
+            "removed": removed,
+            "success": true
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !cli.quiet {
+        if removed > 0 {
+            println!(
+                "{} {} from {}",
+                "Removed".green(),
+                format_diff_value(&value),
+                path.to_string().bold()
+            );
+        } else {
+            println!(
+                "{} does not contain {}",
+                path.to_string().bold(),
+                format_diff_value(&value)
+            );
+        }
+        if let Some(proposal) = &proposal {
+            print_restart_note(proposal);
+        }
+    }
+
+    Ok(())
+}
+
+/// Push `value` onto the array at `path`, creating the array if the setting is
+/// missing. Returns `false` if the array already contains `value`.
+fn add_to_list(settings: &mut Value, path: &SettingPath, value: &Value) -> Result<bool> {
+    match path.get(settings) {
+        None => path.set(settings, Value::Array(vec![value.clone()]))?,
+        Some(Value::Array(items)) if items.contains(value) => return Ok(false),
+        Some(Value::Array(_)) => path
+            .join(PathSegment::Append)
+            .set(settings, value.clone())?,
+        Some(_) => anyhow::bail!("{path} is not a list"),
+    }
+    Ok(true)
+}
+
+/// Remove every element equal to `value` from the array at `path`, returning
+/// how many were removed
+fn remove_from_list(settings: &mut Value, path: &SettingPath, value: &Value) -> Result<usize> {
+    let items = match path.get(settings) {
+        Some(Value::Array(items)) => items,
+        Some(_) => anyhow::bail!("{path} is not a list"),
+        None => return Err(schema::not_found(settings, path)),
+    };
+
+    let kept: Vec<Value> = items
+        .iter()
+        .filter(|item| *item != value)
+        .cloned()
+        .collect();
+    let removed = items.len() - kept.len();
+    if removed > 0 {
+        path.set(settings, Value::Array(kept))?;
+    }
+    Ok(removed)
+}
+
+/// Reset all settings to defaults (factory reset)
+pub async fn reset(cli: &Cli, no_backup: bool) -> Result<()> {
+    info!("Resetting settings to defaults");
+
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    // Snapshot the current settings first so the reset can be rolled back
+    let backup = if no_backup {
+        None
+    } else {
+        let settings = client
+            .settings()
+            .await
+            .context("Failed to back up settings before reset (use --no-backup to skip)")?;
+        Some(backup::save_backup(
+            &backup::backups_dir()?,
+            &settings,
+            Local::now(),
+        )?)
+    };
+
+    if !cli.quiet && !cli.json {
+        if let Some(backup) = &backup {
+            println!(
+                "{} current settings to {}",
+                "Backed up".green(),
+                backup.path.display()
+            );
+        }
+        println!(
+            "{}",
+            "Resetting Rancher Desktop to factory defaults...".yellow()
+        );
+    }
+
+    client.factory_reset().await?;
+
+    if cli.json {
+        let output = serde_json::json!({
+            "success": true,
+            "message": "Settings reset to defaults",
+            "backup": backup
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !cli.quiet {
+        println!("{}", "Settings reset to defaults.".green());
+        println!();
+        println!(
+            "{} Rancher Desktop may need to restart.",
+            "Note:".yellow().bold()
+        );
+        if let Some(backup) = &backup {
+            println!("To undo: rh settings restore {}", backup.name);
+        }
+    }
+
+    Ok(())
+}
+
+/// List the settings backups taken before factory resets
+pub fn list_backups(cli: &Cli) -> Result<()> {
+    let backups = backup::list_backups(&backup::backups_dir()?)?;
+
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&backups)?);
+    } else if cli.quiet {
+        for backup in &backups {
+            println!("{}", backup.name);
+        }
+    } else if backups.is_empty() {
+        println!("No settings backups found.");
+    } else {
+        println!("{}", "Settings backups".bold().cyan());
+        println!("{}", "=".repeat(40));
+        for backup in &backups {
+            println!(
+                "  {}  {}",
+                backup.name,
+                backup.created.as_deref().unwrap_or("").dimmed()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply the settings saved in `backup` (a backup name or file path), or only
+/// validate them if `dry_run`
+pub async fn restore(cli: &Cli, backup: &str, dry_run: bool) -> Result<()> {
+    info!("Restoring settings from {}", backup);
+
+    let path = backup::resolve_backup(&backup::backups_dir()?, backup)?;
+    let settings = read_settings_file(&path, Some(SettingsFormat::Json))?;
+
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    let Some(proposal) = apply_document(cli, &client, &settings, dry_run).await? else {
+        return Ok(());
+    };
+
+    if cli.json {
+        let output = serde_json::json!({
+            "file": path,
+            "success": true,
+            "requiresRestart": proposal.requires_restart
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !cli.quiet {
+        println!("{} settings from {}", "Restored".green(), path.display());
+        print_restart_note(&proposal);
+    }
+
+    Ok(())
+}
+
+/// Export all settings to `output` (or stdout)
+pub async fn export(
+    cli: &Cli,
+    output: Option<&Path>,
+    format: Option<SettingsFormat>,
+) -> Result<()> {
+    info!("Exporting settings");
+
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    let settings = client.settings().await?;
+
+    let format =
+        format.unwrap_or_else(|| output.map_or(SettingsFormat::Json, SettingsFormat::from_path));
+    let document = serialize_settings(&settings, format)?;
+
+    match output {
+        Some(path) => {
+            fs::write(path, &document)
+                .with_context(|| format!("Failed to write settings to {}", path.display()))?;
+
+            if cli.json {
+                let output = serde_json::json!({
+                    "file": path,
+                    "success": true
+                });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+            } else if !cli.quiet {
+                println!("{} settings to {}", "Exported".green(), path.display());
+            }
+        }
+        None => print!("{document}"),
+    }
+
+    Ok(())
+}
+
+/// Validate settings from `file` and apply them, or only validate them if `dry_run`
+pub async fn import(
+    cli: &Cli,
+    file: &Path,
+    format: Option<SettingsFormat>,
+    dry_run: bool,
+) -> Result<()> {
+    info!("Importing settings from {}", file.display());
+
+    let settings = read_settings_file(file, format)?;
+
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    let Some(proposal) = apply_document(cli, &client, &settings, dry_run).await? else {
+        return Ok(());
+    };
+
+    if cli.json {
+        let output = serde_json::json!({
+            "file": file,
+            "success": true,
+            "requiresRestart": proposal.requires_restart
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !cli.quiet {
+        println!("{} settings from {}", "Imported".green(), file.display());
+        print_restart_note(&proposal);
+    }
+
+    Ok(())
+}
+
+/// A single difference between two settings documents
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "lowercase")]
+pub enum SettingChange {
+    Added {
+        path: String,
+        value: Value,
+    },
+    Removed {
+        path: String,
+        value: Value,
+    },
+    Changed {
+        path: String,
+        from: Value,
+        to: Value,
+    },
+}
+
+impl SettingChange {
+    /// The dot-path of the changed setting
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Added { path, .. } | Self::Removed { path, .. } | Self::Changed { path, .. } => {
+                path
+            }
+        }
+    }
+}
+
+/// Output structure for the settings diff command
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsDiffOutput {
+    pub file: std::path::PathBuf,
+    pub changes: Vec<SettingChange>,
+    pub requires_restart: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Value>,
+}
+
+/// Compare the current settings with what applying `file` would produce.
+///
+/// A partial file is merged into the current settings first, so only the
+/// settings it changes are reported. Fails after reporting if the proposal
+/// has validation errors.
+pub async fn diff(cli: &Cli, file: &Path, format: Option<SettingsFormat>) -> Result<()> {
+    info!("Comparing settings with {}", file.display());
+
+    let desired = read_settings_file(file, format)?;
+
+    let client = RdClient::from_cli(cli)
+        .context("Failed to load Rancher Desktop configuration. Is Rancher Desktop running?")?;
+
+    let current = client.settings().await?;
+    let changes = diff_settings(&current, &merge_settings(&current, &desired));
+    let proposal = client.propose_settings(&desired).await?;
+    let valid = !proposal.has_errors();
+
+    if cli.json {
+        let output = SettingsDiffOutput {
+            file: file.to_path_buf(),
+            changes,
+            requires_restart: proposal.requires_restart,
+            errors: if valid { None } else { proposal.errors.clone() },
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        print_diff(&changes);
+
+        if valid && !changes.is_empty() && proposal.requires_restart {
+            println!();
+            println!(
+                "{} Applying these changes requires a restart.",
+                "Note:".yellow().bold()
+            );
+        }
+    }
+
+    if !valid {
+        anyhow::bail!(
+            "Invalid settings: {}",
+            serde_json::to_string_pretty(&proposal.errors)?
+        );
+    }
+
+    Ok(())
+}
+
+/// Deep-merge `overlay` into `base`, the way Rancher Desktop applies partial settings
+fn merge_settings(base: &Value, overlay: &Value) -> Value {
+    match (base, overlay) {
+        (Value::Object(base_obj), Value::Object(overlay_obj)) => {
+            let mut merged = base_obj.clone();
+            for (key, value) in overlay_obj {
+                let entry = match base_obj.get(key) {
+                    Some(existing) => merge_settings(existing, value),
+                    None => value.clone(),
+                };
+                merged.insert(key.clone(), entry);
+            }
+            Value::Object(merged)
+        }
+        _ => overlay.clone(),
+    }
+}
+
+/// Structurally diff two settings documents by dot-path.
+///
+/// Objects are compared key by key; arrays and scalars are compared as whole values.
+fn diff_settings(current: &Value, desired: &Value) -> Vec<SettingChange> {
+    let mut changes = Vec::new();
+    diff_at(&SettingPath::default(), current, desired, &mut changes);
+    changes
+}
+
+fn diff_at(path: &SettingPath, current: &Value, desired: &Value, changes: &mut Vec<SettingChange>) {
+    match (current, desired) {
+        (Value::Object(cur), Value::Object(des)) => {
+            for (key, cur_val) in cur {
+                let child = path.join(PathSegment::Key(key.clone()));
+                match des.get(key) {
+                    Some(des_val) => diff_at(&child, cur_val, des_val, changes),
+                    None => changes.push(SettingChange::Removed {
+                        path: child.to_string(),
+                        value: cur_val.clone(),
+                    }),
+                }
+            }
+            for (key, des_val) in des {
+                if !cur.contains_key(key) {
+                    changes.push(SettingChange::Added {
+                        path: path.join(PathSegment::Key(key.clone())).to_string(),
+                        value: des_val.clone(),
+                    });
+                }
+            }
+        }
+        _ if current != desired => changes.push(SettingChange::Changed {
+            path: path.to_string(),
+            from: current.clone(),
+            to: desired.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn print_diff(changes: &[SettingChange]) {
+    if changes.is_empty() {
+        println!("{}", "No differences.".green());
+        return;
+    }
+
+    for change in changes {
+        match change {
+            SettingChange::Added { path, value } => {
+                println!(
+                    "{} {}: {}",
+                    "+".green(),
+                    path.green(),
+                    format_diff_value(value)
+                );
+            }
+            SettingChange::Removed { path, value } => {
+                println!("{} {}: {}", "-".red(), path.red(), format_diff_value(value));
+            }
+            SettingChange::Changed { path, from, to } => {
+                println!(
+                    "{} {}: {} -> {}",
+                    "~".yellow(),
+                    path.yellow(),
+                    format_diff_value(from),
+                    format_diff_value(to)
+                );
+            }
+        }
+    }
+
+    println!();
+    println!("{} setting(s) differ", changes.len());
+}
+
+/// Like `format_value`, but keeps arrays and objects on one line
+fn format_diff_value(value: &Value) -> String {
+    match value {
+        Value::Array(_) | Value::Object(_) => value.to_string(),
+        _ => format_value(value),
+    }
+}
+
+/// Output structure for `--dry-run`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunOutput {
+    pub dry_run: bool,
+    pub valid: bool,
+    pub changes: Vec<SettingChange>,
+    pub requires_restart: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Value>,
+}
+
+/// Propose `settings` without applying them and report what would change.
+///
+/// Fails after reporting if the proposal has validation errors, so a dry
+/// run can gate pre-merge checks.
+async fn report_dry_run(
+    cli: &Cli,
+    client: &RdClient,
+    current: &Value,
+    settings: &Value,
+) -> Result<()> {
+    let proposal = client.propose_settings(settings).await?;
+    let changes = diff_settings(current, settings);
+    let valid = !proposal.has_errors();
+
+    if cli.json {
+        let output = DryRunOutput {
+            dry_run: true,
+            valid,
+            changes,
+            requires_restart: proposal.requires_restart,
+            errors: if valid { None } else { proposal.errors.clone() },
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if !cli.quiet {
+        println!("{}", "Dry run - no settings were changed.".cyan());
+        println!();
+        print_diff(&changes);
+
+        if valid && !changes.is_empty() && proposal.requires_restart {
+            println!();
+            println!(
+                "{} Applying these changes requires a restart.",
+                "Note:".yellow().bold()
+            );
+        }
+    }
+
+    if !valid {
+        anyhow::bail!(
+            "Invalid settings: {}",
+            serde_json::to_string_pretty(&proposal.errors)?
+        );
+    }
+
+    Ok(())
+}
+
+/// Validate and apply a complete or partial settings document, or only report
+/// what it would change if `dry_run`. Returns the proposal when it was applied.
+pub(crate) async fn apply_document(
+    cli: &Cli,
+    client: &RdClient,
+    settings: &Value,
+    dry_run: bool,
+) -> Result<Option<SettingsProposal>> {
+    // Rancher Desktop merges a partial document into the current settings
+    let current = client.settings().await?;
+    let merged = merge_settings(&current, settings);
+    check_locked(client, &current, &merged).await?;
+
+    if dry_run {
+        report_dry_run(cli, client, &current, &merged).await?;
+        return Ok(None);
+    }
+
+    let proposal = validate(client, settings).await?;
+    client.put_settings(settings).await?;
+    Ok(Some(proposal))
+}
+
+/// Validate and apply an edited copy of `current`, or only report the change
+/// if `dry_run`. Returns the proposal when the settings were applied.
+async fn apply_settings(
+    cli: &Cli,
+    client: &RdClient,
+    current: &Value,
+    settings: &Value,
+    dry_run: bool,
+) -> Result<Option<SettingsProposal>> {
+    check_locked(client, current, settings).await?;
+
+    if dry_run {
+        report_dry_run(cli, client, current, settings).await?;
+        return Ok(None);
+    }
+
+    let proposal = validate(client, settings).await?;
+    client.put_settings(settings).await?;
+    Ok(Some(proposal))
+}
+
+/// Fail with a clear message if changing `current` to `settings` would touch
+/// a setting locked by a deployment profile
+async fn check_locked(client: &RdClient, current: &Value, settings: &Value) -> Result<()> {
+    let changes = diff_settings(current, settings);
+    if changes.is_empty() {
+        return Ok(());
+    }
+    locked::fetch(client).await.check(&changes)
+}
+
+pub(crate) fn print_restart_note(proposal: &SettingsProposal) {
+    if proposal.requires_restart {
+        println!();
+        println!(
+            "{} Restart required for changes to take effect.",
+            "Note:".yellow().bold()
+        );
+    }
+}
+
+/// Check settings with `propose_settings`, failing with the reported errors
+async fn validate(client: &RdClient, settings: &Value) -> Result<SettingsProposal> {
+    let proposal = client.propose_settings(settings).await?;
+
+    if proposal.has_errors() {
+        anyhow::bail!(
+            "Invalid settings: {}",
+            serde_json::to_string_pretty(&proposal.errors)?
+        );
+    }
+
+    Ok(proposal)
+}
+
+/// Read a settings document from a JSON or YAML file
+fn read_settings_file(path: &Path, format: Option<SettingsFormat>) -> Result<Value> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read settings file: {}", path.display()))?;
+    let format = format.unwrap_or_else(|| SettingsFormat::from_path(path));
+
+    parse_settings(&content, format)
+        .with_context(|| format!("Failed to parse settings file: {}", path.display()))
+}
+
+/// Parse a settings document, which must be an object
+fn parse_settings(content: &str, format: SettingsFormat) -> Result<Value> {
+    let settings: Value = match format {
+        SettingsFormat::Json => serde_json::from_str(content)?,
+        SettingsFormat::Yaml => serde_yaml::from_str(content)?,
+    };
+
+    if !settings.is_object() {
+        anyhow::bail!("Settings document must be an object");
+    }
+
+    Ok(settings)
+}
+
+/// Serialize settings in the requested format, ending with a newline
+fn serialize_settings(settings: &Value, format: SettingsFormat) -> Result<String> {
+    match format {
+        SettingsFormat::Json => Ok(format!("{}\n", serde_json::to_string_pretty(settings)?)),
+        SettingsFormat::Yaml => Ok(serde_yaml::to_string(settings)?),
+    }
+}
+
+/// Parse a value string as JSON or return as string
+fn parse_value(value: &str) -> Value {
+    // Try to parse as JSON first
+    if let Ok(json) = serde_json::from_str(value) {
+        return json;
+    }
+
+    // Check for common boolean values
+    match value.to_lowercase().as_str() {
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        _ => {}
+    }
+
+    // Check for numbers
+    if let Ok(n) = value.parse::<i64>() {
+        return Value::Number(n.into());
+    }
+    if let Ok(n) = value.parse::<f64>() {
+        if let Some(num) = serde_json::Number::from_f64(n) {
+            return Value::Number(num);
+        }
+    }
+
+    // Fall back to string
+    Value::String(value.to_string())
+}
+
+/// Format a JSON value for display
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => "null".dimmed().to_string(),
+        Value::Bool(b) => {
+            if *b {
+                "true".green().to_string()
+            } else {
+                "false".red().to_string()
+            }
+        }
+        Value::Number(n) => n.to_string().cyan().to_string(),
+        Value::String(s) => format!("\"{s}\"").yellow().to_string(),
+        Value::Array(_) | Value::Object(_) => {
+            serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+        }
+    }
+}
+
+/// Print settings as a tree structure
+pub(crate) fn print_settings_tree(value: &Value, indent: usize) {
+    print_tree(
+        value,
+        &SettingPath::default(),
+        &locked::LockedSettings::default(),
+        indent,
+    );
+}
+
+/// Print settings as a tree, marking the settings in `locked`
+fn print_tree(value: &Value, path: &SettingPath, locked: &locked::LockedSettings, indent: usize) {
+    let prefix = "  ".repeat(indent);
+
+    match value {
+        Value::Object(obj) => {
+            for (key, val) in obj {
+                let child = path.join(PathSegment::Key(key.clone()));
+                let marker = if !val.is_object() && locked.is_locked(&child) {
+                    format!(" {}", "[locked]".red())
+                } else {
+                    String::new()
+                };
+                match val {
+                    Value::Object(_) => {
+                        println!("{}{}:", prefix, key.bold());
+                        print_tree(val, &child, locked, indent + 1);
+                    }
+                    Value::Array(arr) => {
+                        println!("{}{}: [{}", prefix, key.bold(), marker);
+                        for item in arr {
+                            print_tree(item, &child, locked, indent + 1);
+                        }
+                        println!("{prefix}]");
+                    }
+                    _ => {
+                        println!("{}{}: {}{}", prefix, key, format_value(val), marker);
+                    }
+                }
+            }
+        }
+        _ => {
+            println!("{}{}", prefix, format_value(value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_value_at_path() {
+        let settings = serde_json::json!({
+            "kubernetes": {
+                "version": "1.28.0",
+                "enabled": true
+            },
+            "containerEngine": {
+                "name": "containerd"
+            }
+        });
+
+        assert_eq!(
+            "kubernetes.version"
+                .parse::<SettingPath>()
+                .unwrap()
+                .get(&settings),
+            Some(&Value::String("1.28.0".to_string()))
+        );
+        assert_eq!(
+            "kubernetes.enabled"
+                .parse::<SettingPath>()
+                .unwrap()
+                .get(&settings),
+            Some(&Value::Bool(true))
+        );
+        assert_eq!(
+            "containerEngine.name"
+                .parse::<SettingPath>()
+                .unwrap()
+                .get(&settings),
+            Some(&Value::String("containerd".to_string()))
+        );
+        assert_eq!(
+            "nonexistent".parse::<SettingPath>().unwrap().get(&settings),
+            None
+        );
+        assert_eq!(
+            "kubernetes.nonexistent"
+                .parse::<SettingPath>()
+                .unwrap()
+                .get(&settings),
+            None
+        );
+    }
+
+    #[test]
+    fn test_set_value_at_path() {
+        let mut settings = serde_json::json!({
+            "kubernetes": {
+                "version": "1.28.0",
+                "enabled": true
+            }
+        });
+
+        "kubernetes.version"
+            .parse::<SettingPath>()
+            .unwrap()
+            .set(&mut settings, Value::String("1.29.0".to_string()))
+            .unwrap();
+
+        assert_eq!(
+            settings["kubernetes"]["version"],
+            Value::String("1.29.0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_assignment_args() {
+        let args = |a: &[&str]| a.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        // Legacy `PATH VALUE` form
+        assert_eq!(
+            parse_assignment_args(&args(&["kubernetes.version", "1.29.0"])).unwrap(),
+            vec![RawAssignment {
+                path: "kubernetes.version".parse().unwrap(),
+                value: "1.29.0".to_string(),
+            }]
+        );
+
+        let assignments = parse_assignment_args(&args(&[
+            "kubernetes.enabled=true",
+            "containerEngine.name=moby",
+            "virtualMachine.memoryInGB=8",
+        ]))
+        .unwrap();
+        assert_eq!(assignments.len(), 3);
+        assert_eq!(assignments[0].value, "true");
+        assert_eq!(assignments[2].value, "8");
+
+        // Values may themselves contain '='
+        assert_eq!(
+            parse_assignment("application.extraArgs=--foo=bar")
+                .unwrap()
+                .value,
+            "--foo=bar"
+        );
+
+        // ...and quoted keys may too
+        let quoted = parse_assignment(r#"a."x=y"[0]=1"#).unwrap();
+        assert_eq!(quoted.path.to_string(), r#"a."x=y"[0]"#);
+        assert_eq!(quoted.value, "1");
+
+        assert!(parse_assignment_args(&args(&["kubernetes.enabled"])).is_err());
+        assert!(parse_assignment("=true").is_err());
+    }
+
+    #[test]
+    fn test_parse_assignment_lines() {
+        let input =
+            "# team baseline\nkubernetes.enabled = true\n\ncontainerEngine.name=containerd\n";
+        let assignments = parse_assignment_lines(input).unwrap();
+        assert_eq!(
+            assignments,
+            vec![
+                RawAssignment {
+                    path: "kubernetes.enabled".parse().unwrap(),
+                    value: "true".to_string(),
+                },
+                RawAssignment {
+                    path: "containerEngine.name".parse().unwrap(),
+                    value: "containerd".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_coerce_assignment() {
+        let settings = serde_json::json!({
+            "kubernetes": { "version": "1.29.0", "enabled": true },
+            "WSL": { "integrations": {} }
+        });
+        let path = |s: &str| s.parse::<SettingPath>().unwrap();
+
+        assert_eq!(
+            coerce_assignment(&settings, &path("kubernetes.version"), "1.30", false).unwrap(),
+            Value::from("1.30")
+        );
+        assert!(coerce_assignment(&settings, &path("kubernetes.enabled"), "on", false).is_err());
+
+        // New keys need --allow-new, and then fall back to guessing the type
+        let distro = path("WSL.integrations.Ubuntu");
+        assert!(coerce_assignment(&settings, &distro, "true", false).is_err());
+        assert_eq!(
+            coerce_assignment(&settings, &distro, "true", true).unwrap(),
+            Value::Bool(true)
+        );
+    }
+
+    #[test]
+    fn test_add_to_list() {
+        let mut settings = serde_json::json!({
+            "containerEngine": { "allowedImages": { "patterns": ["docker.io"] } },
+            "kubernetes": { "version": "1.29.0" }
+        });
+        let patterns: SettingPath = "containerEngine.allowedImages.patterns".parse().unwrap();
+
+        assert!(add_to_list(&mut settings, &patterns, &Value::from("ghcr.io")).unwrap());
+        assert!(!add_to_list(&mut settings, &patterns, &Value::from("docker.io")).unwrap());
+        assert_eq!(
+            settings["containerEngine"]["allowedImages"]["patterns"],
+            serde_json::json!(["docker.io", "ghcr.io"])
+        );
+
+        // A missing list is created
+        let extra: SettingPath = "kubernetes.options".parse().unwrap();
+        assert!(add_to_list(&mut settings, &extra, &Value::from("x")).unwrap());
+        assert_eq!(settings["kubernetes"]["options"], serde_json::json!(["x"]));
+
+        let version: SettingPath = "kubernetes.version".parse().unwrap();
+        assert!(add_to_list(&mut settings, &version, &Value::from("x")).is_err());
+    }
+
+    #[test]
+    fn test_remove_from_list() {
+        let mut settings = serde_json::json!({
+            "patterns": ["docker.io", "ghcr.io", "docker.io"],
+            "name": "moby"
+        });
+        let patterns: SettingPath = "patterns".parse().unwrap();
+
+        assert_eq!(
+            remove_from_list(&mut settings, &patterns, &Value::from("docker.io")).unwrap(),
+            2
+        );
+        assert_eq!(
+            remove_from_list(&mut settings, &patterns, &Value::from("quay.io")).unwrap(),
+            0
+        );
+        assert_eq!(settings["patterns"], serde_json::json!(["ghcr.io"]));
+
+        let name: SettingPath = "name".parse().unwrap();
+        assert!(remove_from_list(&mut settings, &name, &Value::from("moby")).is_err());
+        let missing: SettingPath = "missing".parse().unwrap();
+        assert!(remove_from_list(&mut settings, &missing, &Value::from("x")).is_err());
+    }
+
+    #[test]
+    fn test_diff_settings() {
+        let current = serde_json::json!({
+            "kubernetes": {"version": "1.28.0", "enabled": true, "port": 6443},
+            "containerEngine": {"name": "containerd"},
+            "images": ["a"]
+        });
+        let desired = serde_json::json!({
+            "kubernetes": {"version": "1.29.0", "enabled": true},
+            "containerEngine": {"name": "containerd", "allowedImages": {"enabled": true}},
+            "images": ["a", "b"]
+        });
+
+        let changes = diff_settings(&current, &desired);
+        assert_eq!(
+            changes,
+            vec![
+                SettingChange::Added {
+                    path: "containerEngine.allowedImages".to_string(),
+                    value: serde_json::json!({"enabled": true}),
+                },
+                SettingChange::Changed {
+                    path: "images".to_string(),
+                    from: serde_json::json!(["a"]),
+                    to: serde_json::json!(["a", "b"]),
+                },
+                SettingChange::Removed {
+                    path: "kubernetes.port".to_string(),
+                    value: serde_json::json!(6443),
+                },
+                SettingChange::Changed {
+                    path: "kubernetes.version".to_string(),
+                    from: serde_json::json!("1.28.0"),
+                    to: serde_json::json!("1.29.0"),
+                },
+            ]
+        );
+
+        assert!(diff_settings(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_merge_settings() {
+        let current = serde_json::json!({
+            "kubernetes": {"version": "1.28.0", "enabled": true},
+            "containerEngine": {"name": "moby"}
+        });
+        let partial = serde_json::json!({
+            "kubernetes": {"version": "1.29.0"},
+            "application": {"telemetry": {"enabled": false}}
+        });
+
+        let merged = merge_settings(&current, &partial);
+        assert_eq!(
+            merged,
+            serde_json::json!({
+                "kubernetes": {"version": "1.29.0", "enabled": true},
+                "containerEngine": {"name": "moby"},
+                "application": {"telemetry": {"enabled": false}}
+            })
+        );
+
+        // A partial document never removes settings
+        assert!(diff_settings(&current, &merged)
+            .iter()
+            .all(|c| !matches!(c, SettingChange::Removed { .. })));
+    }
+
+    #[test]
+    fn test_setting_change_serialization() {
+        let change = SettingChange::Changed {
+            path: "kubernetes.version".to_string(),
+            from: serde_json::json!("1.28.0"),
+            to: serde_json::json!("1.29.0"),
+        };
+        assert_eq!(
+            serde_json::to_value(&change).unwrap(),
+            serde_json::json!({
+                "change": "changed",
+                "path": "kubernetes.version",
+                "from": "1.28.0",
+                "to": "1.29.0"
+            })
+        );
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let settings = serde_json::json!({
+            "version": 10,
+            "kubernetes": {"version": "1.33.3", "enabled": true},
+            "containerEngine": {"allowedImages": {"patterns": ["docker.io"]}}
+        });
+
+        for format in [SettingsFormat::Json, SettingsFormat::Yaml] {
+            let document = serialize_settings(&settings, format).unwrap();
+            assert!(document.ends_with('\n'));
+            assert_eq!(parse_settings(&document, format).unwrap(), settings);
+        }
+    }
+
+    #[test]
+    fn test_parse_settings_rejects_non_objects() {
+        assert!(parse_settings("[1, 2]", SettingsFormat::Json).is_err());
+        assert!(parse_settings("- a\n- b\n", SettingsFormat::Yaml).is_err());
+        assert!(parse_settings("kubernetes: {enabled: false}", SettingsFormat::Yaml).is_ok());
+    }
+
+    #[test]
+    fn test_settings_format_from_path() {
+        assert_eq!(
+            SettingsFormat::from_path(Path::new("rd.yaml")),
+            SettingsFormat::Yaml
+        );
+        assert_eq!(
+            SettingsFormat::from_path(Path::new("rd.YML")),
+            SettingsFormat::Yaml
+        );
+        assert_eq!(
+            SettingsFormat::from_path(Path::new("rd.json")),
+            SettingsFormat::Json
+        );
+        assert_eq!(
+            SettingsFormat::from_path(Path::new("settings")),
+            SettingsFormat::Json
+        );
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("true"), Value::Bool(true));
+        assert_eq!(parse_value("false"), Value::Bool(false));
+        assert_eq!(parse_value("42"), Value::Number(42.into()));
+        assert_eq!(parse_value("hello"), Value::String("hello".to_string()));
+        assert_eq!(parse_value("[1, 2, 3]"), serde_json::json!([1, 2, 3]));
+        assert_eq!(
+            parse_value("{\"key\": \"value\"}"),
+            serde_json::json!({"key": "value"})
+        );
+    }
+}
@@ -0,0 +1,405 @@
+//! Settings path grammar.
+//!
+//! A path is a dot-separated list of keys, each optionally followed by array
+//! subscripts:
+//!
+//! - `kubernetes.version` - nested object keys
+//! - `containerEngine.allowedImages.patterns[0]` - an array element
+//! - `containerEngine.allowedImages.patterns[]` - append to an array (last segment only)
+//! - `diagnostics.mutedChecks."key.with.dots"` - a quoted key; `\"` and `\\` escape
+//!   inside quotes
+
+use anyhow::{Context, Result};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SettingPathError {
+    #[error("Empty setting path")]
+    Empty,
+    #[error("Empty key at position {0}")]
+    EmptyKey(usize),
+    #[error("Unterminated quoted key starting at position {0}")]
+    UnterminatedQuote(usize),
+    #[error("Unterminated array index starting at position {0}")]
+    UnterminatedIndex(usize),
+    #[error("Invalid array index: {0}")]
+    InvalidIndex(String),
+    #[error("Unexpected '{0}' at position {1}")]
+    Unexpected(char, usize),
+    #[error("'[]' (append) is only allowed at the end of a path")]
+    AppendNotLast,
+}
+
+/// One step in a [`SettingPath`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// An object key
+    Key(String),
+    /// An array element
+    Index(usize),
+    /// One past the end of an array (`[]`)
+    Append,
+}
+
+/// A parsed settings path such as `a.b[0]` or `a."key.with.dots"`.
+///
+/// The default value is the root of the settings document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SettingPath {
+    segments: Vec<PathSegment>,
+}
+
+impl SettingPath {
+    #[must_use]
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Returns this path extended by one segment
+    #[must_use]
+    pub fn join(&self, segment: PathSegment) -> Self {
+        let mut segments = self.segments.clone();
+        segments.push(segment);
+        Self { segments }
+    }
+
+    /// Returns this path without its last segment, or `None` for the root
+    #[must_use]
+    pub fn parent(&self) -> Option<Self> {
+        let (_, parent) = self.segments.split_last()?;
+        Some(Self {
+            segments: parent.to_vec(),
+        })
+    }
+
+    /// Look up the value at this path
+    #[must_use]
+    pub fn get<'a>(&self, root: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(root, |value, segment| match segment {
+                PathSegment::Key(key) => value.get(key),
+                PathSegment::Index(index) => value.get(index),
+                PathSegment::Append => None,
+            })
+    }
+
+    /// Set the value at this path.
+    ///
+    /// Every segment but the last must already exist. A final key is inserted
+    /// or replaced, a final index must be in bounds, and `[]` pushes.
+    pub fn set(&self, root: &mut Value, new_value: Value) -> Result<()> {
+        let (last, parent) = self.resolve_parent(root)?;
+
+        match (last, parent) {
+            (PathSegment::Key(key), Value::Object(obj)) => {
+                obj.insert(key.clone(), new_value);
+            }
+            (PathSegment::Index(index), Value::Array(arr)) => {
+                let len = arr.len();
+                let slot = arr
+                    .get_mut(*index)
+                    .with_context(|| format!("Index {index} is out of bounds (length {len})"))?;
+                *slot = new_value;
+            }
+            (PathSegment::Append, Value::Array(arr)) => arr.push(new_value),
+            (PathSegment::Key(_), _) => anyhow::bail!("Cannot set value: parent is not an object"),
+            (PathSegment::Index(_) | PathSegment::Append, _) => {
+                anyhow::bail!("Cannot set value: parent is not an array")
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove the value at this path and return it
+    pub fn remove(&self, root: &mut Value) -> Result<Value> {
+        let (last, parent) = self.resolve_parent(root)?;
+
+        let removed = match (last, parent) {
+            (PathSegment::Key(key), Value::Object(obj)) => obj.remove(key),
+            (PathSegment::Index(index), Value::Array(arr)) if *index < arr.len() => {
+                Some(arr.remove(*index))
+            }
+            (PathSegment::Append, _) => anyhow::bail!("Cannot remove '[]'; use an index"),
+            _ => None,
+        };
+
+        removed.with_context(|| format!("Setting not found: {self}"))
+    }
+
+    /// Split off the last segment and resolve the value that contains it
+    fn resolve_parent<'a>(&self, root: &'a mut Value) -> Result<(&PathSegment, &'a mut Value)> {
+        let (last, parents) = self.segments.split_last().context("Empty path")?;
+
+        let mut current = root;
+        for segment in parents {
+            current = match segment {
+                PathSegment::Key(key) => current.get_mut(key),
+                PathSegment::Index(index) => current.get_mut(index),
+                PathSegment::Append => None,
+            }
+            .with_context(|| format!("Path component not found: {}", SegmentDisplay(segment)))?;
+        }
+
+        Ok((last, current))
+    }
+}
+
+impl FromStr for SettingPath {
+    type Err = SettingPathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(SettingPathError::Empty);
+        }
+
+        let mut chars = s.char_indices().peekable();
+        let mut segments = Vec::new();
+
+        loop {
+            // A key, either quoted or bare
+            let start = chars.peek().map_or(s.len(), |&(i, _)| i);
+            let key = if chars.next_if(|&(_, c)| c == '"').is_some() {
+                let mut key = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, c)) => key.push(c),
+                            None => return Err(SettingPathError::UnterminatedQuote(start)),
+                        },
+                        Some((_, c)) => key.push(c),
+                        None => return Err(SettingPathError::UnterminatedQuote(start)),
+                    }
+                }
+                key
+            } else {
+                let mut key = String::new();
+                while let Some((i, c)) = chars.next_if(|&(_, c)| c != '.' && c != '[') {
+                    if c == ']' || c == '"' {
+                        return Err(SettingPathError::Unexpected(c, i));
+                    }
+                    key.push(c);
+                }
+                if key.is_empty() {
+                    return Err(SettingPathError::EmptyKey(start));
+                }
+                key
+            };
+            segments.push(PathSegment::Key(key));
+
+            // Any number of `[N]` or `[]` subscripts
+            while let Some((open, _)) = chars.next_if(|&(_, c)| c == '[') {
+                let mut index = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, ']')) => break,
+                        Some((_, c)) => index.push(c),
+                        None => return Err(SettingPathError::UnterminatedIndex(open)),
+                    }
+                }
+                segments.push(if index.is_empty() {
+                    PathSegment::Append
+                } else {
+                    PathSegment::Index(
+                        index
+                            .parse()
+                            .map_err(|_| SettingPathError::InvalidIndex(index))?,
+                    )
+                });
+            }
+
+            match chars.next() {
+                None => break,
+                Some((_, '.')) => {}
+                Some((i, c)) => return Err(SettingPathError::Unexpected(c, i)),
+            }
+        }
+
+        if segments[..segments.len() - 1].contains(&PathSegment::Append) {
+            return Err(SettingPathError::AppendNotLast);
+        }
+
+        Ok(Self { segments })
+    }
+}
+
+impl fmt::Display for SettingPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 && matches!(segment, PathSegment::Key(_)) {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", SegmentDisplay(segment))?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for SettingPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Renders a single segment, quoting keys that would not parse back as bare keys
+struct SegmentDisplay<'a>(&'a PathSegment);
+
+impl fmt::Display for SegmentDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            PathSegment::Key(key) if needs_quotes(key) => {
+                write!(f, "\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+            PathSegment::Key(key) => f.write_str(key),
+            PathSegment::Index(index) => write!(f, "[{index}]"),
+            PathSegment::Append => f.write_str("[]"),
+        }
+    }
+}
+
+fn needs_quotes(key: &str) -> bool {
+    key.is_empty() || key.contains(['.', '[', ']', '"', '\\', '='])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(s: &str) -> SettingPath {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_setting_path() {
+        assert_eq!(
+            path("kubernetes.version").segments(),
+            [
+                PathSegment::Key("kubernetes".to_string()),
+                PathSegment::Key("version".to_string()),
+            ]
+        );
+        assert_eq!(
+            path("a.patterns[1][]").segments(),
+            [
+                PathSegment::Key("a".to_string()),
+                PathSegment::Key("patterns".to_string()),
+                PathSegment::Index(1),
+                PathSegment::Append,
+            ]
+        );
+        assert_eq!(
+            path(r#"a."key.with \"dots\"".b"#).segments(),
+            [
+                PathSegment::Key("a".to_string()),
+                PathSegment::Key(r#"key.with "dots""#.to_string()),
+                PathSegment::Key("b".to_string()),
+            ]
+        );
+
+        assert_eq!("".parse::<SettingPath>(), Err(SettingPathError::Empty));
+        assert_eq!(
+            "a..b".parse::<SettingPath>(),
+            Err(SettingPathError::EmptyKey(2))
+        );
+        assert_eq!(
+            "a.\"b".parse::<SettingPath>(),
+            Err(SettingPathError::UnterminatedQuote(2))
+        );
+        assert_eq!(
+            "a[0".parse::<SettingPath>(),
+            Err(SettingPathError::UnterminatedIndex(1))
+        );
+        assert_eq!(
+            "a[x]".parse::<SettingPath>(),
+            Err(SettingPathError::InvalidIndex("x".to_string()))
+        );
+        assert_eq!(
+            "a[0]b".parse::<SettingPath>(),
+            Err(SettingPathError::Unexpected('b', 4))
+        );
+        assert_eq!(
+            "a[].b".parse::<SettingPath>(),
+            Err(SettingPathError::AppendNotLast)
+        );
+    }
+
+    #[test]
+    fn test_setting_path_display_round_trip() {
+        for s in [
+            "kubernetes.version",
+            "a.b[0][]",
+            r#"a."key.with.dots"[2]"#,
+            r#"a."q\"\\""#,
+        ] {
+            assert_eq!(path(s).to_string(), s);
+        }
+        assert_eq!(
+            SettingPath::default()
+                .join(PathSegment::Key("x.y".to_string()))
+                .join(PathSegment::Index(0))
+                .to_string(),
+            r#""x.y"[0]"#
+        );
+    }
+
+    #[test]
+    fn test_setting_path_get_set_remove() {
+        let mut settings = serde_json::json!({
+            "containerEngine": {
+                "allowedImages": { "patterns": ["docker.io", "ghcr.io"] }
+            },
+            "dotted": { "key.with.dots": 1 }
+        });
+
+        assert_eq!(
+            path("containerEngine.allowedImages.patterns[1]").get(&settings),
+            Some(&Value::from("ghcr.io"))
+        );
+        assert_eq!(
+            path(r#"dotted."key.with.dots""#).get(&settings),
+            Some(&Value::from(1))
+        );
+        assert_eq!(
+            path("containerEngine.allowedImages.patterns[5]").get(&settings),
+            None
+        );
+        assert_eq!(SettingPath::default().get(&settings), Some(&settings));
+
+        path("containerEngine.allowedImages.patterns[0]")
+            .set(&mut settings, Value::from("quay.io"))
+            .unwrap();
+        path("containerEngine.allowedImages.patterns[]")
+            .set(&mut settings, Value::from("registry.k8s.io"))
+            .unwrap();
+        assert_eq!(
+            settings["containerEngine"]["allowedImages"]["patterns"],
+            serde_json::json!(["quay.io", "ghcr.io", "registry.k8s.io"])
+        );
+        assert!(path("containerEngine.allowedImages.patterns[9]")
+            .set(&mut settings, Value::Null)
+            .is_err());
+        assert!(path("dotted[0]").set(&mut settings, Value::Null).is_err());
+        assert!(path("missing.key").set(&mut settings, Value::Null).is_err());
+
+        assert_eq!(
+            path("containerEngine.allowedImages.patterns[1]")
+                .remove(&mut settings)
+                .unwrap(),
+            Value::from("ghcr.io")
+        );
+        assert_eq!(
+            path(r#"dotted."key.with.dots""#)
+                .remove(&mut settings)
+                .unwrap(),
+            Value::from(1)
+        );
+        assert_eq!(settings["dotted"], serde_json::json!({}));
+        assert!(path("dotted.nope").remove(&mut settings).is_err());
+    }
+}
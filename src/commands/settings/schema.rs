@@ -0,0 +1,238 @@
+//! Type-aware conversion of command-line values.
+//!
+//! Rancher Desktop does not publish a schema for its settings, so the type of
+//! each path is learned from the current settings document: a value written
+//! to `kubernetes.version` must be a string because the current one is, and
+//! `1.30` stays `"1.30"` instead of becoming the number `1.3`.
+
+use super::parse_value;
+use super::path::{PathSegment, SettingPath};
+use anyhow::Result;
+use serde_json::Value;
+
+/// Returns the value a new value at `path` should match: the current value,
+/// or for `[]` the first element of the array
+pub fn expected_value<'a>(settings: &'a Value, path: &SettingPath) -> Option<&'a Value> {
+    match path.segments().split_last() {
+        Some((PathSegment::Append, _)) => path.parent()?.get(settings)?.as_array()?.first(),
+        _ => path.get(settings),
+    }
+}
+
+/// Convert `raw` to the JSON type of `expected`.
+///
+/// With no expected value the type is guessed, the way `parse_value` always did.
+pub fn coerce_value(raw: &str, expected: Option<&Value>) -> Result<Value> {
+    let Some(expected) = expected else {
+        return Ok(parse_value(raw));
+    };
+
+    let value = match expected {
+        Value::Null => parse_value(raw),
+        Value::Bool(_) => match raw.to_lowercase().as_str() {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => anyhow::bail!("Expected true or false, got: {raw}"),
+        },
+        Value::Number(n) if n.is_f64() => raw
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| anyhow::anyhow!("Expected a number, got: {raw}"))?,
+        Value::Number(_) => match (raw.parse::<i64>(), raw.parse::<u64>()) {
+            (Ok(n), _) => Value::from(n),
+            (_, Ok(n)) => Value::from(n),
+            _ => anyhow::bail!("Expected an integer, got: {raw}"),
+        },
+        // A quoted JSON string is unquoted; anything else is taken literally
+        Value::String(_) => match serde_json::from_str(raw) {
+            Ok(Value::String(s)) => Value::String(s),
+            _ => Value::String(raw.to_string()),
+        },
+        Value::Array(_) => match serde_json::from_str(raw) {
+            Ok(value @ Value::Array(_)) => value,
+            _ => anyhow::bail!("Expected a JSON array, got: {raw}"),
+        },
+        Value::Object(_) => match serde_json::from_str(raw) {
+            Ok(value @ Value::Object(_)) => value,
+            _ => anyhow::bail!("Expected a JSON object, got: {raw}"),
+        },
+    };
+
+    Ok(value)
+}
+
+/// Build the error for a path that does not exist, suggesting similar paths
+pub fn not_found(settings: &Value, path: &SettingPath) -> anyhow::Error {
+    let suggestions = suggest_paths(settings, path);
+    if suggestions.is_empty() {
+        anyhow::anyhow!("Setting not found: {path}")
+    } else {
+        let suggestions: Vec<String> = suggestions.iter().map(ToString::to_string).collect();
+        anyhow::anyhow!(
+            "Setting not found: {path} (did you mean {}?)",
+            suggestions.join(", ")
+        )
+    }
+}
+
+/// Up to three existing paths closest to `path` by edit distance
+pub fn suggest_paths(settings: &Value, path: &SettingPath) -> Vec<SettingPath> {
+    let wanted = path.to_string().to_lowercase();
+    let max_distance = (wanted.chars().count() / 5).max(2);
+
+    let mut known = Vec::new();
+    collect_paths(settings, &SettingPath::default(), &mut known);
+
+    let mut candidates: Vec<(usize, SettingPath)> = known
+        .into_iter()
+        .map(|known| {
+            (
+                edit_distance(&wanted, &known.to_string().to_lowercase()),
+                known,
+            )
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    candidates.sort_by_key(|(distance, _)| *distance);
+    candidates
+        .into_iter()
+        .take(3)
+        .map(|(_, path)| path)
+        .collect()
+}
+
+/// Every object key path in `value`; arrays are not descended into
+fn collect_paths(value: &Value, parent: &SettingPath, paths: &mut Vec<SettingPath>) {
+    if let Value::Object(obj) = value {
+        for (key, child) in obj {
+            let path = parent.join(PathSegment::Key(key.clone()));
+            collect_paths(child, &path, paths);
+            paths.push(path);
+        }
+    }
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(s: &str) -> SettingPath {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_coerce_value() {
+        let string = Value::from("1.29.0");
+        let boolean = Value::Bool(false);
+        let integer = Value::from(4);
+        let float = Value::from(0.5);
+
+        // Strings stay strings, even when they look like numbers or booleans
+        assert_eq!(
+            coerce_value("1.30", Some(&string)).unwrap(),
+            Value::from("1.30")
+        );
+        assert_eq!(
+            coerce_value("true", Some(&string)).unwrap(),
+            Value::from("true")
+        );
+        assert_eq!(
+            coerce_value("\"8\"", Some(&string)).unwrap(),
+            Value::from("8")
+        );
+
+        assert_eq!(
+            coerce_value("TRUE", Some(&boolean)).unwrap(),
+            Value::Bool(true)
+        );
+        assert!(coerce_value("yes", Some(&boolean)).is_err());
+
+        assert_eq!(coerce_value("8", Some(&integer)).unwrap(), Value::from(8));
+        assert!(coerce_value("8.5", Some(&integer)).is_err());
+        assert!(coerce_value("eight", Some(&integer)).is_err());
+        assert_eq!(coerce_value("2", Some(&float)).unwrap(), Value::from(2.0));
+
+        assert_eq!(
+            coerce_value(r#"["a"]"#, Some(&serde_json::json!([]))).unwrap(),
+            serde_json::json!(["a"])
+        );
+        assert!(coerce_value("a", Some(&serde_json::json!([]))).is_err());
+        assert!(coerce_value("[]", Some(&serde_json::json!({}))).is_err());
+
+        // Unknown types fall back to guessing
+        assert_eq!(coerce_value("8", None).unwrap(), Value::from(8));
+        assert_eq!(
+            coerce_value("8", Some(&Value::Null)).unwrap(),
+            Value::from(8)
+        );
+    }
+
+    #[test]
+    fn test_expected_value() {
+        let settings = serde_json::json!({
+            "patterns": ["docker.io"],
+            "empty": [],
+            "kubernetes": { "version": "1.29.0" }
+        });
+
+        assert_eq!(
+            expected_value(&settings, &path("kubernetes.version")),
+            Some(&Value::from("1.29.0"))
+        );
+        assert_eq!(
+            expected_value(&settings, &path("patterns[]")),
+            Some(&Value::from("docker.io"))
+        );
+        assert_eq!(expected_value(&settings, &path("empty[]")), None);
+        assert_eq!(expected_value(&settings, &path("kubernetes.port")), None);
+    }
+
+    #[test]
+    fn test_suggest_paths() {
+        let settings = serde_json::json!({
+            "kubernetes": { "version": "1.29.0", "enabled": true },
+            "containerEngine": { "name": "moby" }
+        });
+
+        assert_eq!(
+            suggest_paths(&settings, &path("kubernetes.verison")),
+            vec![path("kubernetes.version")]
+        );
+        assert_eq!(
+            suggest_paths(&settings, &path("ContainerEngine.Name")),
+            vec![path("containerEngine.name")]
+        );
+        assert!(suggest_paths(&settings, &path("virtualMachine.memoryInGB")).is_empty());
+
+        assert_eq!(
+            not_found(&settings, &path("kubernetes.enable")).to_string(),
+            "Setting not found: kubernetes.enable (did you mean kubernetes.enabled?)"
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+}
@@ -4,8 +4,7 @@
 //! when available.
 
 use crate::cli::Cli;
-use crate::client::http::{build_client, HttpClientConfig};
-use crate::config::RdEngineConfig;
+use crate::client::rd::{RdClient, RdClientError};
 use anyhow::Result;
 use colored::Colorize;
 use serde::Serialize;
@@ -37,6 +36,36 @@ pub struct RancherDesktopInfo {
     pub api_endpoint: String,
 }
 
+impl RancherDesktopInfo {
+    /// Extract the Kubernetes and container engine details from a settings document
+    #[must_use]
+    pub fn from_settings(settings: &serde_json::Value, api_endpoint: String) -> Self {
+        let kubernetes_version = settings
+            .get("kubernetes")
+            .and_then(|k| k.get("version"))
+            .and_then(|v| v.as_str())
+            .map(std::string::ToString::to_string);
+
+        let kubernetes_enabled = settings
+            .get("kubernetes")
+            .and_then(|k| k.get("enabled"))
+            .and_then(serde_json::Value::as_bool);
+
+        let container_engine = settings
+            .get("containerEngine")
+            .and_then(|c| c.get("name"))
+            .and_then(|n| n.as_str())
+            .map(std::string::ToString::to_string);
+
+        Self {
+            kubernetes_version,
+            container_engine,
+            kubernetes_enabled,
+            api_endpoint,
+        }
+    }
+}
+
 /// Run the version command
 pub async fn run(cli: &Cli) -> Result<()> {
     let ranch_hand_version = env!("CARGO_PKG_VERSION").to_string();
@@ -82,56 +111,26 @@ pub async fn run(cli: &Cli) -> Result<()> {
 
 /// Try to get Rancher Desktop version and configuration info
 async fn get_rancher_desktop_info(cli: &Cli) -> Option<RancherDesktopInfo> {
-    let config = RdEngineConfig::load().ok()?;
-    let api_endpoint = config.api_base_url();
+    let client = RdClient::from_cli(cli).ok()?;
+    let api_endpoint = client.config().api_base_url();
 
     // Try to fetch settings from the API
-    let client_config = HttpClientConfig::with_timeout(cli.insecure, cli.timeout);
-    let client = build_client(&client_config).ok()?;
-
-    let url = config.api_url("/v1/settings");
-    debug!("Fetching settings from {}", url);
-
-    let response = client
-        .get(&url)
-        .header("Authorization", config.basic_auth())
-        .send()
-        .await
-        .ok()?;
-
-    if !response.status().is_success() {
-        debug!("Settings request failed: {}", response.status());
-        return Some(RancherDesktopInfo {
-            kubernetes_version: None,
-            container_engine: None,
-            kubernetes_enabled: None,
-            api_endpoint,
-        });
-    }
+    let settings = match client.settings().await {
+        Ok(settings) => settings,
+        Err(RdClientError::Status { status, .. }) => {
+            debug!("Settings request failed: {}", status);
+            return Some(RancherDesktopInfo {
+                kubernetes_version: None,
+                container_engine: None,
+                kubernetes_enabled: None,
+                api_endpoint,
+            });
+        }
+        Err(e) => {
+            debug!("Failed to fetch settings: {}", e);
+            return None;
+        }
+    };
 
-    let settings: serde_json::Value = response.json().await.ok()?;
-
-    let kubernetes_version = settings
-        .get("kubernetes")
-        .and_then(|k| k.get("version"))
-        .and_then(|v| v.as_str())
-        .map(std::string::ToString::to_string);
-
-    let kubernetes_enabled = settings
-        .get("kubernetes")
-        .and_then(|k| k.get("enabled"))
-        .and_then(serde_json::Value::as_bool);
-
-    let container_engine = settings
-        .get("containerEngine")
-        .and_then(|c| c.get("name"))
-        .and_then(|n| n.as_str())
-        .map(std::string::ToString::to_string);
-
-    Some(RancherDesktopInfo {
-        kubernetes_version,
-        container_engine,
-        kubernetes_enabled,
-        api_endpoint,
-    })
+    Some(RancherDesktopInfo::from_settings(&settings, api_endpoint))
 }
@@ -0,0 +1,376 @@
+//! Wait command that blocks until Rancher Desktop is usable.
+//!
+//! Conditions are checked in order: the API (rd-engine.json exists and the
+//! API responds), the backend state, and Kubernetes readiness (the k3s API
+//! server's `/readyz`, reached through the `rancher-desktop` kubeconfig
+//! context). Progress is shown with a spinner, or emitted as JSON lines with
+//! `--json`.
+
+use crate::cli::{Cli, WaitCondition};
+use crate::client::http::HttpClientConfig;
+use crate::client::kube::{kubeconfig_path, KubeClient, RANCHER_DESKTOP_CONTEXT};
+use crate::client::rd::{BackendState, RdClient};
+use crate::commands::backend::WaitError;
+use crate::config::{ConfigError, RdEngineConfig};
+use anyhow::Result;
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// How often to re-check a condition that is not yet satisfied
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Outcome of checking a single condition once
+#[derive(Debug, PartialEq, Eq)]
+enum Progress {
+    Ready(String),
+    Pending(String),
+}
+
+/// Kind of progress event emitted in JSON mode
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WaitEventKind {
+    Waiting,
+    Ready,
+    Timeout,
+    Complete,
+}
+
+/// A single JSON progress event, printed one per line
+#[derive(Debug, Serialize)]
+pub struct WaitEvent {
+    pub event: WaitEventKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    pub message: String,
+    pub elapsed_secs: u64,
+}
+
+/// Wait for the requested conditions, failing after `timeout`
+pub async fn run(cli: &Cli, conditions: &[WaitCondition], timeout: Duration) -> Result<()> {
+    info!("Waiting for Rancher Desktop: {:?}", conditions);
+
+    let started = Instant::now();
+    let mut client = None;
+
+    for condition in conditions_in_order(conditions) {
+        wait_for_condition(cli, condition, &mut client, started, timeout).await?;
+    }
+
+    let message = "Rancher Desktop is ready".to_string();
+    if cli.json {
+        emit(&WaitEvent {
+            event: WaitEventKind::Complete,
+            condition: None,
+            message,
+            elapsed_secs: started.elapsed().as_secs(),
+        })?;
+    } else if !cli.quiet {
+        println!("{}", message.green().bold());
+    }
+
+    Ok(())
+}
+
+/// The API is a prerequisite for the other conditions, so it always comes first
+fn conditions_in_order(requested: &[WaitCondition]) -> Vec<WaitCondition> {
+    [
+        WaitCondition::Api,
+        WaitCondition::Backend,
+        WaitCondition::Kubernetes,
+    ]
+    .into_iter()
+    .filter(|c| *c == WaitCondition::Api || requested.contains(c))
+    .collect()
+}
+
+async fn wait_for_condition(
+    cli: &Cli,
+    condition: WaitCondition,
+    client: &mut Option<RdClient>,
+    started: Instant,
+    timeout: Duration,
+) -> Result<()> {
+    let show_progress = !cli.quiet && !cli.json;
+    let spinner = show_progress.then(|| {
+        let sp = ProgressBar::new_spinner();
+        sp.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.cyan} {msg}")
+                .expect("valid spinner template"),
+        );
+        sp.set_message(format!("Waiting for {condition}..."));
+        sp.enable_steady_tick(Duration::from_millis(100));
+        sp
+    });
+
+    let mut last_status = String::new();
+
+    loop {
+        let progress = check_condition(cli, condition, client).await?;
+        let elapsed = started.elapsed();
+
+        match progress {
+            Progress::Ready(message) => {
+                if let Some(sp) = &spinner {
+                    sp.finish_and_clear();
+                }
+                if cli.json {
+                    emit(&WaitEvent {
+                        event: WaitEventKind::Ready,
+                        condition: Some(condition.to_string()),
+                        message,
+                        elapsed_secs: elapsed.as_secs(),
+                    })?;
+                } else if !cli.quiet {
+                    println!("{} {}: {}", "\u{2714}".green(), condition, message);
+                }
+                return Ok(());
+            }
+            Progress::Pending(status) => {
+                debug!("Still waiting for {}: {}", condition, status);
+                if status != last_status {
+                    if let Some(sp) = &spinner {
+                        sp.set_message(format!("Waiting for {condition}: {status}"));
+                    }
+                    if cli.json {
+                        emit(&WaitEvent {
+                            event: WaitEventKind::Waiting,
+                            condition: Some(condition.to_string()),
+                            message: status.clone(),
+                            elapsed_secs: elapsed.as_secs(),
+                        })?;
+                    }
+                    last_status = status;
+                }
+            }
+        }
+
+        if elapsed >= timeout {
+            if let Some(sp) = &spinner {
+                sp.finish_and_clear();
+            }
+            if cli.json {
+                emit(&WaitEvent {
+                    event: WaitEventKind::Timeout,
+                    condition: Some(condition.to_string()),
+                    message: last_status.clone(),
+                    elapsed_secs: elapsed.as_secs(),
+                })?;
+            }
+            return Err(WaitError::ConditionTimeout {
+                condition: condition.to_string(),
+                status: last_status,
+                elapsed,
+            }
+            .into());
+        }
+
+        tokio::time::sleep(POLL_INTERVAL.min(timeout - elapsed)).await;
+    }
+}
+
+/// Check a condition once.
+///
+/// Transient failures are reported as pending; only a backend in the
+/// ERROR state is fatal.
+async fn check_condition(
+    cli: &Cli,
+    condition: WaitCondition,
+    client: &mut Option<RdClient>,
+) -> Result<Progress> {
+    if condition == WaitCondition::Api {
+        return Ok(check_api(cli, client).await);
+    }
+
+    // The API condition always runs first, so a client exists unless it was
+    // reset after an error; re-establish it in that case.
+    if client.is_none() {
+        if let Progress::Pending(status) = check_api(cli, client).await {
+            return Ok(Progress::Pending(status));
+        }
+    }
+    let Some(rd) = client.as_ref() else {
+        return Ok(Progress::Pending("API not reachable".to_string()));
+    };
+
+    let state = match rd.backend_state().await {
+        Ok(state) => state,
+        Err(e) => {
+            *client = None;
+            return Ok(Progress::Pending(e.to_string()));
+        }
+    };
+
+    if state == BackendState::Error {
+        return Err(WaitError::BackendError {
+            target: BackendState::Started,
+        }
+        .into());
+    }
+
+    match condition {
+        WaitCondition::Backend => Ok(backend_progress(&state)),
+        WaitCondition::Kubernetes => match rd.settings().await {
+            Ok(settings) => match kubernetes_progress(&settings, &state) {
+                Some(progress) => Ok(progress),
+                None => Ok(check_kubernetes_api(cli).await),
+            },
+            Err(e) => Ok(Progress::Pending(e.to_string())),
+        },
+        WaitCondition::Api => unreachable!("handled above"),
+    }
+}
+
+/// Load rd-engine.json (it may not exist yet) and probe the API
+async fn check_api(cli: &Cli, client: &mut Option<RdClient>) -> Progress {
+    if client.is_none() {
+        let config = match RdEngineConfig::load_from(cli.config.as_deref()) {
+            Ok(config) => config,
+            Err(ConfigError::NotFound { .. }) => {
+                return Progress::Pending("rd-engine.json not found".to_string())
+            }
+            // The file may be mid-write while Rancher Desktop starts up
+            Err(e) => return Progress::Pending(e.to_string()),
+        };
+        match RdClient::new(
+            config,
+            &HttpClientConfig::with_timeout(cli.insecure, cli.timeout),
+        ) {
+            Ok(rd) => *client = Some(rd),
+            Err(e) => return Progress::Pending(e.to_string()),
+        }
+    }
+
+    let Some(rd) = client.as_ref() else {
+        return Progress::Pending("API not reachable".to_string());
+    };
+
+    match rd.backend_state().await {
+        Ok(_) => Progress::Ready(format!("API reachable at {}", rd.config().api_base_url())),
+        Err(e) => {
+            // Credentials change when Rancher Desktop restarts; reload next time
+            *client = None;
+            Progress::Pending(e.to_string())
+        }
+    }
+}
+
+fn backend_progress(state: &BackendState) -> Progress {
+    if state.satisfies(&BackendState::Started) {
+        Progress::Ready(format!("backend is {state}"))
+    } else {
+        Progress::Pending(format!("backend is {state}"))
+    }
+}
+
+/// Progress known from settings and backend state alone, or `None` once the
+/// backend has started with Kubernetes enabled and the cluster must be probed
+fn kubernetes_progress(settings: &serde_json::Value, state: &BackendState) -> Option<Progress> {
+    let enabled = settings
+        .get("kubernetes")
+        .and_then(|k| k.get("enabled"))
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+
+    if !enabled {
+        Some(Progress::Ready("disabled in settings".to_string()))
+    } else if *state == BackendState::Started {
+        None
+    } else {
+        Some(Progress::Pending(format!("backend is {state}")))
+    }
+}
+
+/// Probe the k3s API server; the kubeconfig may not be written yet
+async fn check_kubernetes_api(cli: &Cli) -> Progress {
+    let client = kubeconfig_path().and_then(|path| {
+        KubeClient::load(&path, RANCHER_DESKTOP_CONTEXT, cli.insecure, cli.timeout)
+    });
+    let client = match client {
+        Ok(client) => client,
+        Err(e) => return Progress::Pending(format!("{e:#}")),
+    };
+
+    match client.readyz().await {
+        Ok(()) => Progress::Ready(format!("Kubernetes API ready at {}", client.server())),
+        Err(e) => Progress::Pending(format!("Kubernetes API not ready: {e:#}")),
+    }
+}
+
+fn emit(event: &WaitEvent) -> Result<()> {
+    println!("{}", serde_json::to_string(event)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conditions_in_order_always_includes_api() {
+        assert_eq!(
+            conditions_in_order(&[WaitCondition::Kubernetes]),
+            vec![WaitCondition::Api, WaitCondition::Kubernetes]
+        );
+        assert_eq!(
+            conditions_in_order(&[WaitCondition::Kubernetes, WaitCondition::Backend]),
+            vec![
+                WaitCondition::Api,
+                WaitCondition::Backend,
+                WaitCondition::Kubernetes
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backend_progress() {
+        assert!(matches!(
+            backend_progress(&BackendState::Started),
+            Progress::Ready(_)
+        ));
+        assert!(matches!(
+            backend_progress(&BackendState::Disabled),
+            Progress::Ready(_)
+        ));
+        assert_eq!(
+            backend_progress(&BackendState::Starting),
+            Progress::Pending("backend is STARTING".to_string())
+        );
+    }
+
+    #[test]
+    fn test_kubernetes_progress() {
+        let enabled = serde_json::json!({"kubernetes": {"enabled": true}});
+        let disabled = serde_json::json!({"kubernetes": {"enabled": false}});
+
+        assert!(matches!(
+            kubernetes_progress(&disabled, &BackendState::Disabled),
+            Some(Progress::Ready(_))
+        ));
+        // A started backend is not enough; the cluster itself is probed
+        assert!(kubernetes_progress(&enabled, &BackendState::Started).is_none());
+        assert!(matches!(
+            kubernetes_progress(&enabled, &BackendState::Starting),
+            Some(Progress::Pending(_))
+        ));
+    }
+
+    #[test]
+    fn test_wait_event_serialization() {
+        let event = WaitEvent {
+            event: WaitEventKind::Ready,
+            condition: Some("api".to_string()),
+            message: "API reachable".to_string(),
+            elapsed_secs: 3,
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            json,
+            r#"{"event":"ready","condition":"api","message":"API reachable","elapsed_secs":3}"#
+        );
+    }
+}
@@ -1,12 +1,9 @@
-// This module provides configuration infrastructure that will be used by command implementations.
-// Allow dead_code during infrastructure phase - will be removed when commands are implemented.
-#![allow(dead_code)]
-
 use crate::paths;
+use crate::releases::MirrorLayout;
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Default port for the Rancher Desktop API
@@ -15,6 +12,9 @@ pub const DEFAULT_API_PORT: u16 = 6107;
 /// Default host for the Rancher Desktop API
 pub const DEFAULT_API_HOST: &str = "127.0.0.1";
 
+/// Environment variable that overrides the location of rd-engine.json
+pub const CONFIG_ENV_VAR: &str = "RH_CONFIG";
+
 #[derive(Error, Debug)]
 pub enum ConfigError {
     #[error("rd-engine.json not found at {path}\n\nThis file is created when Rancher Desktop is running.\nPlease ensure Rancher Desktop is started and try again.\n\nExpected location: {path}")]
@@ -63,15 +63,36 @@ impl Default for RdEngineConfig {
 }
 
 impl RdEngineConfig {
-    /// Load configuration from rd-engine.json
+    /// Resolve the location of rd-engine.json.
+    ///
+    /// Resolution order:
+    /// 1. `explicit` (the `--config` flag)
+    /// 2. The `RH_CONFIG` environment variable
+    /// 3. The platform default from [`paths::rd_engine_json_path`]
+    pub fn resolve_path(explicit: Option<&Path>) -> Result<PathBuf, ConfigError> {
+        if let Some(path) = explicit {
+            return Ok(path.to_path_buf());
+        }
+
+        if let Some(path) = std::env::var_os(CONFIG_ENV_VAR).filter(|p| !p.is_empty()) {
+            return Ok(PathBuf::from(path));
+        }
+
+        paths::rd_engine_json_path().map_err(|e| ConfigError::ReadError(e.to_string()))
+    }
+
+    /// Load configuration from rd-engine.json, honoring `RH_CONFIG`
     pub fn load() -> Result<Self, ConfigError> {
-        let path =
-            paths::rd_engine_json_path().map_err(|e| ConfigError::ReadError(e.to_string()))?;
-        Self::load_from_path(&path)
+        Self::load_from(None)
+    }
+
+    /// Load configuration from an explicit path, `RH_CONFIG`, or the platform default
+    pub fn load_from(explicit: Option<&Path>) -> Result<Self, ConfigError> {
+        Self::load_from_path(&Self::resolve_path(explicit)?)
     }
 
     /// Load configuration from a specific path
-    pub fn load_from_path(path: &PathBuf) -> Result<Self, ConfigError> {
+    pub fn load_from_path(path: &Path) -> Result<Self, ConfigError> {
         let path_str = path.display().to_string();
         let contents = fs::read_to_string(path).map_err(|e| match e.kind() {
             std::io::ErrorKind::NotFound => ConfigError::NotFound { path: path_str },
@@ -82,11 +103,13 @@ impl RdEngineConfig {
     }
 
     /// Try to load config, returning None if not found (Rancher Desktop not running)
+    #[must_use]
     pub fn try_load() -> Option<Self> {
         Self::load().ok()
     }
 
     /// Get the base URL for the Rancher Desktop API
+    #[must_use]
     pub fn api_base_url(&self) -> String {
         format!("http://{}:{}", self.host, self.port)
     }
@@ -103,12 +126,14 @@ impl RdEngineConfig {
     /// - `api_url("/v1/settings")` → `http://127.0.0.1:6107/v1/settings`
     /// - `api_url("v1/settings")` → `http://127.0.0.1:6107/v1/settings`
     /// - `api_url("///v1/settings")` → `http://127.0.0.1:6107/v1/settings`
+    #[must_use]
     pub fn api_url(&self, endpoint: &str) -> String {
         let endpoint = endpoint.trim_start_matches('/');
         format!("{}/{}", self.api_base_url(), endpoint)
     }
 
     /// Get basic auth header value
+    #[must_use]
     pub fn basic_auth(&self) -> String {
         use base64::Engine;
         let credentials = format!("{}:{}", self.user, self.password);
@@ -117,6 +142,35 @@ impl RdEngineConfig {
     }
 }
 
+/// ranch-hand's own settings, from [`paths::ranch_hand_config_path`]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RanchHandConfig {
+    /// Base URL of a k3s release mirror to use instead of GitHub
+    pub k3s_mirror: Option<String>,
+    /// How the mirror lays out its files
+    pub k3s_mirror_layout: Option<MirrorLayout>,
+}
+
+impl RanchHandConfig {
+    /// Load the ranch-hand config file, or the defaults if there is none
+    pub fn load() -> Result<Self> {
+        Self::load_from_path(&paths::ranch_hand_config_path()?)
+    }
+
+    /// Load from a specific path, or the defaults if it does not exist
+    pub fn load_from_path(path: &Path) -> Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+}
+
 /// Runtime configuration combining file config with CLI overrides
 #[derive(Debug, Clone)]
 pub struct AppConfig {
@@ -138,12 +192,9 @@ pub struct AppConfig {
 
 impl AppConfig {
     /// Create config from CLI arguments
+    #[must_use]
     pub fn from_cli(cli: &crate::cli::Cli) -> Self {
-        let rd_engine = if let Some(config_path) = &cli.config {
-            RdEngineConfig::load_from_path(config_path).ok()
-        } else {
-            RdEngineConfig::try_load()
-        };
+        let rd_engine = RdEngineConfig::load_from(cli.config.as_deref()).ok();
 
         Self {
             rd_engine,
@@ -157,6 +208,7 @@ impl AppConfig {
     }
 
     /// Check if Rancher Desktop API is available
+    #[must_use]
     pub fn has_api_config(&self) -> bool {
         self.rd_engine.is_some()
     }
@@ -190,7 +242,7 @@ mod tests {
         }"#;
         let file = create_temp_config(content);
 
-        let config = RdEngineConfig::load_from_path(&file.path().to_path_buf()).unwrap();
+        let config = RdEngineConfig::load_from_path(file.path()).unwrap();
         assert_eq!(config.user, "admin");
         assert_eq!(config.password, "secret123");
         assert_eq!(config.port, 6107);
@@ -207,7 +259,7 @@ mod tests {
         }"#;
         let file = create_temp_config(content);
 
-        let config = RdEngineConfig::load_from_path(&file.path().to_path_buf()).unwrap();
+        let config = RdEngineConfig::load_from_path(file.path()).unwrap();
         assert_eq!(config.host, "localhost");
         assert_eq!(config.port, 8080);
     }
@@ -252,17 +304,53 @@ mod tests {
 
     #[test]
     fn test_config_not_found() {
-        let result =
-            RdEngineConfig::load_from_path(&PathBuf::from("/nonexistent/path/rd-engine.json"));
+        let result = RdEngineConfig::load_from_path(Path::new("/nonexistent/path/rd-engine.json"));
         assert!(matches!(result, Err(ConfigError::NotFound { .. })));
     }
 
+    #[test]
+    fn test_resolve_path_prefers_explicit() {
+        let explicit = Path::new("/custom/profile/rd-engine.json");
+        assert_eq!(
+            RdEngineConfig::resolve_path(Some(explicit)).unwrap(),
+            explicit
+        );
+    }
+
+    #[test]
+    fn test_load_from_explicit_path() {
+        let file = create_temp_config(r#"{"user": "admin", "password": "secret", "port": 7000}"#);
+
+        let config = RdEngineConfig::load_from(Some(file.path())).unwrap();
+        assert_eq!(config.port, 7000);
+    }
+
+    #[test]
+    fn test_load_ranch_hand_config() {
+        let file = create_temp_config(
+            r#"{"k3sMirror": "https://nexus.example.com/k3s", "k3sMirrorLayout": "directory"}"#,
+        );
+        let config = RanchHandConfig::load_from_path(file.path()).unwrap();
+        assert_eq!(
+            config.k3s_mirror.as_deref(),
+            Some("https://nexus.example.com/k3s")
+        );
+        assert_eq!(config.k3s_mirror_layout, Some(MirrorLayout::Directory));
+
+        let missing =
+            RanchHandConfig::load_from_path(Path::new("/nonexistent/config.json")).unwrap();
+        assert!(missing.k3s_mirror.is_none());
+
+        let invalid = create_temp_config(r#"{"k3sMirrorLayout": "flat"}"#);
+        assert!(RanchHandConfig::load_from_path(invalid.path()).is_err());
+    }
+
     #[test]
     fn test_invalid_json() {
         let content = "not valid json";
         let file = create_temp_config(content);
 
-        let result = RdEngineConfig::load_from_path(&file.path().to_path_buf());
+        let result = RdEngineConfig::load_from_path(file.path());
         assert!(matches!(result, Err(ConfigError::ParseError(_))));
     }
 }
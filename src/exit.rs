@@ -0,0 +1,215 @@
+//! Process exit codes.
+//!
+//! Every `rh` command exits with a stable, documented code so scripts and CI
+//! can tell why a command failed, not just that it did:
+//!
+//! | Code | Meaning                                         |
+//! |------|-------------------------------------------------|
+//! | 0    | Success (or healthy)                            |
+//! | 1    | Checks reported warnings (only with `--strict`) |
+//! | 2    | Failure                                         |
+//! | 3    | Rancher Desktop is not running                  |
+//! | 4    | Configuration error                             |
+//! | 5    | Timed out waiting for Rancher Desktop           |
+//! | 6    | Backend entered the ERROR state                 |
+//! | 64   | Invalid command line (unknown flag, bad value)  |
+//!
+//! Commands return typed errors; `main` maps them to a code with
+//! [`ExitStatus::from_error`].
+
+use crate::client::http::HttpClientError;
+use crate::client::rd::RdClientError;
+use crate::commands::backend::WaitError;
+use crate::config::ConfigError;
+use crate::paths::PathError;
+use thiserror::Error;
+
+/// Exit status of an `rh` invocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    Success,
+    Warnings,
+    Failure,
+    NotRunning,
+    ConfigError,
+    Timeout,
+    BackendError,
+    Usage,
+}
+
+impl ExitStatus {
+    /// Numeric process exit code
+    #[must_use]
+    pub fn code(self) -> u8 {
+        match self {
+            Self::Success => 0,
+            Self::Warnings => 1,
+            Self::Failure => 2,
+            Self::NotRunning => 3,
+            Self::ConfigError => 4,
+            Self::Timeout => 5,
+            Self::BackendError => 6,
+            // EX_USAGE from sysexits.h, so bad flags never look like failed checks
+            Self::Usage => 64,
+        }
+    }
+
+    /// Classify an error returned by a command.
+    ///
+    /// Walks the context chain, so errors wrapped with `.context(...)` keep
+    /// their code. Anything unrecognised is a generic failure.
+    #[must_use]
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        error.chain().find_map(classify).unwrap_or(Self::Failure)
+    }
+}
+
+impl From<ExitStatus> for std::process::ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        Self::from(status.code())
+    }
+}
+
+/// Errors for check commands (`diagnose`, `certs check`) whose results were not healthy
+#[derive(Error, Debug)]
+pub enum CheckError {
+    #[error("{count} check(s) failed")]
+    Failed { count: usize },
+    #[error("{count} check(s) reported warnings (--strict)")]
+    Warnings { count: usize },
+    #[error("Rancher Desktop is not running")]
+    NotRunning,
+}
+
+impl CheckError {
+    /// Error for a set of check results, if they should fail the command
+    #[must_use]
+    pub fn from_counts(fail: usize, warn: usize, strict: bool) -> Option<Self> {
+        if fail > 0 {
+            Some(Self::Failed { count: fail })
+        } else if strict && warn > 0 {
+            Some(Self::Warnings { count: warn })
+        } else {
+            None
+        }
+    }
+}
+
+fn classify(error: &(dyn std::error::Error + 'static)) -> Option<ExitStatus> {
+    if let Some(e) = error.downcast_ref::<CheckError>() {
+        return Some(match e {
+            CheckError::Failed { .. } => ExitStatus::Failure,
+            CheckError::Warnings { .. } => ExitStatus::Warnings,
+            CheckError::NotRunning => ExitStatus::NotRunning,
+        });
+    }
+    if let Some(e) = error.downcast_ref::<WaitError>() {
+        return Some(e.exit_status());
+    }
+    if let Some(e) = error.downcast_ref::<RdClientError>() {
+        return match e {
+            RdClientError::Config(e) => Some(config_status(e)),
+            // Only API connections imply Rancher Desktop is down; a refused
+            // download connection is an ordinary failure.
+            RdClientError::Http(HttpClientError::ConnectionRefused) => Some(ExitStatus::NotRunning),
+            _ => Some(ExitStatus::Failure),
+        };
+    }
+    if let Some(e) = error.downcast_ref::<ConfigError>() {
+        return Some(config_status(e));
+    }
+    if error.downcast_ref::<PathError>().is_some() {
+        return Some(ExitStatus::ConfigError);
+    }
+    None
+}
+
+/// rd-engine.json only exists while Rancher Desktop is running, so a missing
+/// file means "not running"; an unreadable or malformed one is a config error.
+fn config_status(error: &ConfigError) -> ExitStatus {
+    match error {
+        ConfigError::NotFound { .. } => ExitStatus::NotRunning,
+        ConfigError::ReadError(_) | ConfigError::ParseError(_) => ExitStatus::ConfigError,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::rd::BackendState;
+    use anyhow::Context;
+    use std::time::Duration;
+
+    #[test]
+    fn test_exit_codes_are_stable() {
+        assert_eq!(ExitStatus::Success.code(), 0);
+        assert_eq!(ExitStatus::Warnings.code(), 1);
+        assert_eq!(ExitStatus::Failure.code(), 2);
+        assert_eq!(ExitStatus::NotRunning.code(), 3);
+        assert_eq!(ExitStatus::ConfigError.code(), 4);
+        assert_eq!(ExitStatus::Timeout.code(), 5);
+        assert_eq!(ExitStatus::BackendError.code(), 6);
+        assert_eq!(ExitStatus::Usage.code(), 64);
+    }
+
+    #[test]
+    fn test_from_error_through_context() {
+        let err: anyhow::Error = RdClientError::Http(HttpClientError::ConnectionRefused).into();
+        let err = err.context("Failed to get backend state");
+        assert_eq!(ExitStatus::from_error(&err), ExitStatus::NotRunning);
+
+        let err = Err::<(), _>(RdClientError::Config(ConfigError::ParseError(
+            "bad json".to_string(),
+        )))
+        .context("Failed to load Rancher Desktop configuration")
+        .unwrap_err();
+        assert_eq!(ExitStatus::from_error(&err), ExitStatus::ConfigError);
+
+        let err: anyhow::Error = ConfigError::NotFound {
+            path: "rd-engine.json".to_string(),
+        }
+        .into();
+        assert_eq!(ExitStatus::from_error(&err), ExitStatus::NotRunning);
+    }
+
+    #[test]
+    fn test_from_error_wait_and_checks() {
+        let err: anyhow::Error = WaitError::Timeout {
+            target: BackendState::Started,
+            last_state: BackendState::Starting,
+            elapsed: Duration::from_secs(1),
+        }
+        .into();
+        assert_eq!(ExitStatus::from_error(&err), ExitStatus::Timeout);
+
+        let err: anyhow::Error = CheckError::Warnings { count: 2 }.into();
+        assert_eq!(ExitStatus::from_error(&err), ExitStatus::Warnings);
+    }
+
+    #[test]
+    fn test_from_error_unknown_is_failure() {
+        let err = anyhow::anyhow!("Setting not found: foo.bar");
+        assert_eq!(ExitStatus::from_error(&err), ExitStatus::Failure);
+
+        // A refused download is not evidence that Rancher Desktop is down
+        let err: anyhow::Error = HttpClientError::ConnectionRefused.into();
+        assert_eq!(ExitStatus::from_error(&err), ExitStatus::Failure);
+    }
+
+    #[test]
+    fn test_check_error_from_counts() {
+        assert!(CheckError::from_counts(0, 3, false).is_none());
+        assert!(matches!(
+            CheckError::from_counts(0, 3, true),
+            Some(CheckError::Warnings { count: 3 })
+        ));
+        assert!(matches!(
+            CheckError::from_counts(1, 3, true),
+            Some(CheckError::Failed { count: 1 })
+        ));
+        assert!(CheckError::from_counts(0, 0, true).is_none());
+
+        let err: anyhow::Error = CheckError::NotRunning.into();
+        assert_eq!(ExitStatus::from_error(&err), ExitStatus::NotRunning);
+    }
+}
@@ -0,0 +1,42 @@
+//! Library for managing Rancher Desktop.
+//!
+//! The `rh` binary is a thin front end over this crate. The modules are public
+//! so other tooling can load `rd-engine.json`, talk to the Rancher Desktop API,
+//! inspect the k3s cache, and run diagnostic checks without shelling out to
+//! `rh --json`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use ranch_hand::{HttpClientConfig, RdClient, RdEngineConfig};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = RdEngineConfig::load()?;
+//! let client = RdClient::new(config, &HttpClientConfig::default())?;
+//! println!("Backend is {}", client.backend_state().await?);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod cli;
+pub mod client;
+pub mod commands;
+pub mod config;
+pub mod constants;
+pub mod exit;
+pub mod paths;
+pub mod releases;
+pub mod utils;
+
+pub use client::http::{HttpClientConfig, HttpClientError};
+pub use client::rd::{BackendState, RdClient, RdClientError, SettingsProposal};
+pub use commands::cache::{scan_cache_versions, CachedFile, CachedVersion};
+pub use commands::certs::{check_endpoint, CertCheckResult, CertificateInfo};
+pub use commands::diagnose::{CheckResult, CheckStatus};
+pub use config::{ConfigError, RdEngineConfig};
+pub use exit::{CheckError, ExitStatus};
+pub use paths::PathError;
+pub use utils::checksum::{
+    calculate_file_hash, parse_checksum_file, verify_file, verify_file_from_checksums,
+    ChecksumError,
+};
@@ -1,14 +1,13 @@
-mod cli;
-mod client;
-mod commands;
-mod config;
-pub mod constants;
-mod paths;
-mod utils;
-
 use anyhow::Result;
-use clap::Parser;
-use cli::{CacheCommands, CertsCommands, Cli, Commands, SettingsCommands};
+use clap::{CommandFactory, Parser};
+use ranch_hand::cli::{
+    BackupsCommands, CacheCommands, CertsCommands, Cli, Commands, ProfileCommands, SettingsCommands,
+};
+use ranch_hand::commands;
+use ranch_hand::exit::ExitStatus;
+use ranch_hand::utils::download::RetryPolicy;
+use std::process::ExitCode;
+use std::time::Duration;
 use tracing::Level;
 use tracing_subscriber::{fmt, EnvFilter};
 
@@ -48,18 +47,64 @@ fn init_tracing(verbose: u8, quiet: bool) {
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+async fn main() -> ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) if e.use_stderr() => {
+            let _ = e.print();
+            return ExitStatus::Usage.into();
+        }
+        // --help and --version
+        Err(e) => e.exit(),
+    };
     init_tracing(cli.verbose, cli.quiet);
 
+    match run(&cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            ExitStatus::from_error(&e).into()
+        }
+    }
+}
+
+async fn run(cli: &Cli) -> Result<()> {
     match &cli.command {
         Commands::Cache { command } => match command {
-            CacheCommands::List => commands::cache::list(&cli).await,
-            CacheCommands::Populate { version, force } => {
-                commands::cache::populate(&cli, version.as_deref(), *force).await
+            CacheCommands::List => commands::cache::list(cli).await,
+            CacheCommands::Populate {
+                version,
+                force,
+                retries,
+                retry_delay,
+            } => {
+                let retry = RetryPolicy {
+                    retries: *retries,
+                    base_delay: Duration::from_secs(*retry_delay),
+                };
+                commands::cache::populate(cli, version.as_deref(), *force, retry).await
+            }
+            CacheCommands::Export { version, output } => {
+                commands::cache::export(cli, version, output.as_deref()).await
+            }
+            CacheCommands::Import { bundle, force } => {
+                commands::cache::import(cli, bundle, *force).await
+            }
+            CacheCommands::Verify {
+                version,
+                all: _,
+                repair,
+                retries,
+                retry_delay,
+            } => {
+                let repair = repair.then_some(RetryPolicy {
+                    retries: *retries,
+                    base_delay: Duration::from_secs(*retry_delay),
+                });
+                commands::cache::verify(cli, version.as_deref(), repair).await
             }
         },
-        Commands::Diagnose => commands::diagnose::run(&cli).await,
+        Commands::Diagnose { strict } => commands::diagnose::run(cli, *strict).await,
         Commands::Api {
             endpoint,
             method,
@@ -68,7 +113,7 @@ async fn main() -> Result<()> {
             raw,
         } => {
             commands::api::run(
-                &cli,
+                cli,
                 endpoint,
                 method.clone(),
                 body.clone(),
@@ -78,20 +123,119 @@ async fn main() -> Result<()> {
             .await
         }
         Commands::Certs { command } => match command {
-            CertsCommands::Check => commands::certs::check(&cli).await,
+            CertsCommands::Check { strict } => commands::certs::check(cli, *strict).await,
         },
-        Commands::Version => commands::version::run(&cli).await,
-        Commands::Start => commands::backend::start(&cli).await,
-        Commands::Stop => commands::backend::stop(&cli).await,
-        Commands::Restart => commands::backend::restart(&cli).await,
-        Commands::Status => commands::backend::status(&cli).await,
-        Commands::Settings { command } => match command {
-            None => commands::settings::show_all(&cli).await,
-            Some(SettingsCommands::Get { path }) => commands::settings::get(&cli, path).await,
-            Some(SettingsCommands::Set { path, value }) => {
-                commands::settings::set(&cli, path, value).await
+        Commands::Version => commands::version::run(cli).await,
+        Commands::Start { wait, wait_timeout } => {
+            let wait = wait.then(|| Duration::from_secs(*wait_timeout));
+            commands::backend::start(cli, wait).await
+        }
+        Commands::Stop { wait, wait_timeout } => {
+            let wait = wait.then(|| Duration::from_secs(*wait_timeout));
+            commands::backend::stop(cli, wait).await
+        }
+        Commands::Restart {
+            no_wait,
+            wait_timeout,
+        } => commands::backend::restart(cli, !*no_wait, Duration::from_secs(*wait_timeout)).await,
+        Commands::Status {
+            details,
+            watch,
+            interval,
+        } => {
+            if *watch {
+                commands::backend::watch(cli, Duration::from_secs(*interval)).await
+            } else {
+                commands::backend::status(cli, *details).await
             }
-            Some(SettingsCommands::Reset) => commands::settings::reset(&cli).await,
-        },
+        }
+        Commands::Wait {
+            conditions,
+            wait_timeout,
+        } => commands::wait::run(cli, conditions, Duration::from_secs(*wait_timeout)).await,
+        Commands::Settings { command } => run_settings(cli, command.as_ref()).await,
+        Commands::Profile { command } => run_profile(cli, command).await,
+        Commands::Completions { shell } => {
+            clap_complete::generate(*shell, &mut Cli::command(), "rh", &mut std::io::stdout());
+            Ok(())
+        }
+    }
+}
+
+async fn run_settings(cli: &Cli, command: Option<&SettingsCommands>) -> Result<()> {
+    match command {
+        None => commands::settings::show_all(cli).await,
+        Some(SettingsCommands::Get { path }) => commands::settings::get(cli, path).await,
+        Some(SettingsCommands::Explain { path }) => {
+            commands::settings::explain(cli, path.as_deref()).await
+        }
+        Some(SettingsCommands::Set {
+            path,
+            assignments,
+            from_stdin,
+            allow_new,
+            dry_run,
+        }) => {
+            let args: Vec<String> = path.iter().chain(assignments).cloned().collect();
+            commands::settings::set(cli, &args, *from_stdin, *allow_new, *dry_run).await
+        }
+        Some(SettingsCommands::Unset { path, dry_run }) => {
+            commands::settings::unset(cli, path, *dry_run).await
+        }
+        Some(SettingsCommands::Add {
+            path,
+            value,
+            dry_run,
+        }) => commands::settings::add(cli, path, value, *dry_run).await,
+        Some(SettingsCommands::Remove {
+            path,
+            value,
+            dry_run,
+        }) => commands::settings::remove(cli, path, value, *dry_run).await,
+        Some(SettingsCommands::Reset { no_backup }) => {
+            commands::settings::reset(cli, *no_backup).await
+        }
+        Some(SettingsCommands::Backups {
+            command: BackupsCommands::List,
+        }) => commands::settings::list_backups(cli),
+        Some(SettingsCommands::Restore { backup, dry_run }) => {
+            commands::settings::restore(cli, backup, *dry_run).await
+        }
+        Some(SettingsCommands::Export { output, format }) => {
+            commands::settings::export(cli, output.as_deref(), *format).await
+        }
+        Some(SettingsCommands::Import {
+            file,
+            format,
+            dry_run,
+        }) => commands::settings::import(cli, file, *format, *dry_run).await,
+        Some(SettingsCommands::Edit { format }) => commands::settings::edit(cli, *format).await,
+        Some(SettingsCommands::Diff { file, format }) => {
+            commands::settings::diff(cli, file, *format).await
+        }
+    }
+}
+
+async fn run_profile(cli: &Cli, command: &ProfileCommands) -> Result<()> {
+    match command {
+        ProfileCommands::Save { name, force } => commands::profile::save(cli, name, *force).await,
+        ProfileCommands::List => commands::profile::list(cli),
+        ProfileCommands::Show { name } => commands::profile::show(cli, name),
+        ProfileCommands::Apply {
+            name,
+            dry_run,
+            restart,
+            wait_timeout,
+        } => {
+            commands::profile::apply(
+                cli,
+                name,
+                *dry_run,
+                *restart,
+                Duration::from_secs(*wait_timeout),
+            )
+            .await
+        }
+        ProfileCommands::Delete { name } => commands::profile::delete(cli, name),
     }
 }
@@ -9,6 +9,8 @@ pub enum PathError {
     NoCacheDir,
     #[error("Could not determine data directory for this platform")]
     NoDataDir,
+    #[error("Could not determine config directory for this platform")]
+    NoConfigDir,
 }
 
 /// Returns the base cache directory for Rancher Desktop k3s files.
@@ -80,7 +82,6 @@ pub fn rd_engine_json_path() -> Result<PathBuf, PathError> {
 /// - Windows: %LOCALAPPDATA%\rancher-desktop
 /// - macOS: ~/Library/Application Support/rancher-desktop
 /// - Linux: ~/.local/share/rancher-desktop
-#[allow(dead_code)] // Will be used by future commands
 pub fn rancher_desktop_data_dir() -> Result<PathBuf, PathError> {
     #[cfg(target_os = "macos")]
     {
@@ -104,7 +105,38 @@ pub fn rancher_desktop_data_dir() -> Result<PathBuf, PathError> {
     }
 }
 
+/// Returns the directory where ranch-hand keeps its own state.
+///
+/// Platform-specific paths:
+/// - Windows: %LOCALAPPDATA%\ranch-hand
+/// - macOS: ~/Library/Application Support/ranch-hand
+/// - Linux: ~/.local/share/ranch-hand
+pub fn ranch_hand_data_dir() -> Result<PathBuf, PathError> {
+    dirs::data_local_dir()
+        .map(|p| p.join("ranch-hand"))
+        .ok_or(PathError::NoDataDir)
+}
+
+/// Returns the ranch-hand config directory.
+///
+/// Platform-specific paths:
+/// - Windows: %APPDATA%\ranch-hand
+/// - macOS: ~/Library/Application Support/ranch-hand
+/// - Linux: ~/.config/ranch-hand
+pub fn ranch_hand_config_dir() -> Result<PathBuf, PathError> {
+    dirs::config_dir()
+        .map(|p| p.join("ranch-hand"))
+        .ok_or(PathError::NoConfigDir)
+}
+
+/// Returns the path to the ranch-hand config file (`config.json` in
+/// [`ranch_hand_config_dir`]).
+pub fn ranch_hand_config_path() -> Result<PathBuf, PathError> {
+    Ok(ranch_hand_config_dir()?.join("config.json"))
+}
+
 /// Returns the current system architecture string for k3s downloads.
+#[must_use]
 pub fn arch_string() -> &'static str {
     #[cfg(target_arch = "x86_64")]
     {
@@ -123,6 +155,7 @@ pub fn arch_string() -> &'static str {
 }
 
 /// Returns the k3s binary name for the current architecture.
+#[must_use]
 pub fn k3s_binary_name() -> &'static str {
     #[cfg(target_arch = "x86_64")]
     {
@@ -163,6 +196,18 @@ mod tests {
         assert!(path.to_string_lossy().contains("rd-engine.json"));
     }
 
+    #[test]
+    fn test_ranch_hand_data_dir() {
+        let path = ranch_hand_data_dir().expect("Should get ranch-hand data dir");
+        assert!(path.ends_with("ranch-hand"));
+    }
+
+    #[test]
+    fn test_ranch_hand_config_path() {
+        let path = ranch_hand_config_path().expect("Should get ranch-hand config path");
+        assert!(path.ends_with("ranch-hand/config.json"));
+    }
+
     #[test]
     fn test_arch_string() {
         let arch = arch_string();
@@ -0,0 +1,381 @@
+//! Where k3s releases are listed and downloaded from.
+//!
+//! By default releases come from GitHub. Networks that only allow an internal
+//! artifact repository (Artifactory, Nexus, ...) can point ranch-hand at a
+//! mirror with `--mirror`, `RH_K3S_MIRROR`, or `k3sMirror` in the ranch-hand
+//! config file. Two mirror layouts are supported:
+//!
+//! - `github`: the mirror proxies GitHub, serving release assets at
+//!   `<mirror>/k3s-io/k3s/releases/download/<version>/<file>` and the releases
+//!   API at `<mirror>/repos/k3s-io/k3s/releases`
+//! - `directory`: a plain directory per release, `<mirror>/<version>/<file>`,
+//!   with versions read from the directory listing at `<mirror>/`
+
+use crate::cli::Cli;
+use crate::config::RanchHandConfig;
+use crate::constants::{extract_domain, REQUIRED_ENDPOINTS};
+use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// GitHub repository k3s is released from
+const K3S_REPOSITORY: &str = "k3s-io/k3s";
+
+/// k3s release base URL
+const K3S_RELEASES_URL: &str = "https://github.com/k3s-io/k3s/releases/download";
+
+/// k3s releases API URL
+const K3S_RELEASES_API_URL: &str = "https://api.github.com/repos/k3s-io/k3s/releases";
+
+/// Maximum number of versions to fetch from the releases API
+const MAX_VERSIONS_TO_FETCH: usize = 50;
+
+/// How a k3s mirror lays out its files
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MirrorLayout {
+    /// Same paths as github.com and api.github.com
+    #[default]
+    Github,
+    /// One directory per release version
+    Directory,
+}
+
+impl std::fmt::Display for MirrorLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Github => write!(f, "github"),
+            Self::Directory => write!(f, "directory"),
+        }
+    }
+}
+
+/// GitHub release response structure
+#[derive(Debug, Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    prerelease: bool,
+    draft: bool,
+}
+
+/// The source of k3s releases
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum K3sReleases {
+    #[default]
+    GitHub,
+    Mirror {
+        base: String,
+        layout: MirrorLayout,
+    },
+}
+
+impl K3sReleases {
+    /// A mirror at `base`, which must be an http(s) URL
+    pub fn mirror(base: &str, layout: MirrorLayout) -> Result<Self> {
+        let base = base.trim().trim_end_matches('/');
+        let parsed =
+            url::Url::parse(base).with_context(|| format!("Invalid mirror URL: {base}"))?;
+        if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+            anyhow::bail!("Invalid mirror URL: {base} (expected an http or https URL)");
+        }
+
+        Ok(Self::Mirror {
+            base: base.to_string(),
+            layout,
+        })
+    }
+
+    /// Resolve the release source.
+    ///
+    /// Resolution order:
+    /// 1. `--mirror` / `RH_K3S_MIRROR`
+    /// 2. `k3sMirror` in the ranch-hand config file
+    /// 3. GitHub
+    ///
+    /// The layout comes from `--mirror-layout` / `RH_K3S_MIRROR_LAYOUT`, then
+    /// `k3sMirrorLayout` in the config file.
+    pub fn from_cli(cli: &Cli) -> Result<Self> {
+        let config = RanchHandConfig::load()?;
+
+        let Some(base) = cli
+            .mirror
+            .clone()
+            .filter(|m| !m.is_empty())
+            .or(config.k3s_mirror)
+        else {
+            return Ok(Self::GitHub);
+        };
+
+        let layout = cli
+            .mirror_layout
+            .or(config.k3s_mirror_layout)
+            .unwrap_or_default();
+        Self::mirror(&base, layout)
+    }
+
+    /// URL of `file` in release `version`
+    #[must_use]
+    pub fn download_url(&self, version: &str, file: &str) -> String {
+        match self {
+            Self::GitHub => format!("{K3S_RELEASES_URL}/{version}/{file}"),
+            Self::Mirror {
+                base,
+                layout: MirrorLayout::Github,
+            } => format!("{base}/{K3S_REPOSITORY}/releases/download/{version}/{file}"),
+            Self::Mirror {
+                base,
+                layout: MirrorLayout::Directory,
+            } => format!("{base}/{version}/{file}"),
+        }
+    }
+
+    /// URL that lists the available releases
+    #[must_use]
+    pub fn list_url(&self) -> String {
+        match self {
+            Self::GitHub => format!("{K3S_RELEASES_API_URL}?per_page={MAX_VERSIONS_TO_FETCH}"),
+            Self::Mirror {
+                base,
+                layout: MirrorLayout::Github,
+            } => format!("{base}/repos/{K3S_REPOSITORY}/releases?per_page={MAX_VERSIONS_TO_FETCH}"),
+            Self::Mirror {
+                base,
+                layout: MirrorLayout::Directory,
+            } => format!("{base}/"),
+        }
+    }
+
+    /// Parse the response from [`list_url`](Self::list_url) into stable
+    /// versions, newest first
+    pub fn parse_versions(&self, body: &str) -> Result<Vec<String>> {
+        let versions = match self {
+            Self::GitHub
+            | Self::Mirror {
+                layout: MirrorLayout::Github,
+                ..
+            } => {
+                let releases: Vec<GitHubRelease> = serde_json::from_str(body)
+                    .context("Failed to parse GitHub releases response")?;
+                releases
+                    .into_iter()
+                    .filter(|r| !r.prerelease && !r.draft)
+                    .map(|r| r.tag_name)
+                    .collect()
+            }
+            Self::Mirror {
+                layout: MirrorLayout::Directory,
+                ..
+            } => versions_in_listing(body),
+        };
+
+        if versions.is_empty() {
+            return Err(anyhow!(
+                "No stable k3s releases found at {}",
+                self.list_url()
+            ));
+        }
+
+        Ok(versions)
+    }
+
+    /// Endpoints to probe for connectivity: the endpoints Rancher Desktop
+    /// needs, with the GitHub ones replaced by the mirror when there is one
+    #[must_use]
+    pub fn endpoints(&self) -> Vec<(String, String)> {
+        let mut endpoints: Vec<(String, String)> = Vec::new();
+        if let Self::Mirror { base, .. } = self {
+            endpoints.push(("K3s Mirror".to_string(), base.clone()));
+        }
+
+        endpoints.extend(
+            REQUIRED_ENDPOINTS
+                .iter()
+                .filter(|(_, url)| !(self.is_mirror() && is_github(url)))
+                .map(|(name, url)| ((*name).to_string(), (*url).to_string())),
+        );
+        endpoints
+    }
+
+    /// The host releases are downloaded from, for DNS checks
+    #[must_use]
+    pub fn domain(&self) -> String {
+        match self {
+            Self::GitHub => "api.github.com".to_string(),
+            Self::Mirror { base, .. } => extract_domain(base).unwrap_or_else(|| base.clone()),
+        }
+    }
+
+    #[must_use]
+    pub fn is_mirror(&self) -> bool {
+        matches!(self, Self::Mirror { .. })
+    }
+}
+
+impl std::fmt::Display for K3sReleases {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GitHub => write!(f, "GitHub"),
+            Self::Mirror { base, layout } => write!(f, "{base} ({layout} layout)"),
+        }
+    }
+}
+
+fn is_github(url: &str) -> bool {
+    extract_domain(url).is_some_and(|domain| {
+        domain == "github.com"
+            || domain.ends_with(".github.com")
+            || domain.ends_with(".githubusercontent.com")
+    })
+}
+
+/// Find k3s release names (`v1.33.3+k3s1`) in a directory listing, which may
+/// be HTML, JSON or plain text and may URL-encode the `+`
+fn versions_in_listing(listing: &str) -> Vec<String> {
+    let listing = listing.replace("%2B", "+").replace("%2b", "+");
+
+    let mut versions: Vec<(Vec<u64>, String)> = Vec::new();
+    for (start, _) in listing.match_indices('v') {
+        let Some(version) = release_name_at(&listing[start..]) else {
+            continue;
+        };
+        if versions.iter().all(|(_, v)| v != version) {
+            let key = version
+                .split(|c: char| !c.is_ascii_digit())
+                .filter_map(|n| n.parse().ok())
+                .collect();
+            versions.push((key, version.to_string()));
+        }
+    }
+
+    versions.sort_by(|a, b| b.0.cmp(&a.0));
+    versions.into_iter().map(|(_, v)| v).collect()
+}
+
+/// Whether `name` is exactly a stable k3s release name (`vX.Y.Z+k3sN`)
+#[must_use]
+pub fn is_release_name(name: &str) -> bool {
+    name.starts_with('v') && release_name_at(name) == Some(name)
+}
+
+/// The `vX.Y.Z+k3sN` release name at the start of `s`, if there is one
+fn release_name_at(s: &str) -> Option<&str> {
+    fn digits(s: &str) -> usize {
+        s.bytes().take_while(u8::is_ascii_digit).count()
+    }
+
+    // Pre-releases (`v1.34.0-rc1+k3s1`) fail the `+k3s` separator check
+    let mut end = 1;
+    for separator in [".", ".", "+k3s"] {
+        let n = digits(&s[end..]);
+        if n == 0 || !s[end + n..].starts_with(separator) {
+            return None;
+        }
+        end += n + separator.len();
+    }
+    let n = digits(&s[end..]);
+    (n > 0).then_some(&s[..end + n])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_download_and_list_urls() {
+        let github = K3sReleases::GitHub;
+        assert_eq!(
+            github.download_url("v1.33.3+k3s1", "k3s"),
+            "https://github.com/k3s-io/k3s/releases/download/v1.33.3+k3s1/k3s"
+        );
+
+        let proxy = K3sReleases::mirror(
+            "https://artifactory.example.com/artifactory/github/",
+            MirrorLayout::Github,
+        )
+        .unwrap();
+        assert_eq!(
+            proxy.download_url("v1.33.3+k3s1", "k3s"),
+            "https://artifactory.example.com/artifactory/github/k3s-io/k3s/releases/download/v1.33.3+k3s1/k3s"
+        );
+        assert_eq!(
+            proxy.list_url(),
+            "https://artifactory.example.com/artifactory/github/repos/k3s-io/k3s/releases?per_page=50"
+        );
+
+        let directory = K3sReleases::mirror(
+            "https://nexus.example.com/repository/k3s",
+            MirrorLayout::Directory,
+        )
+        .unwrap();
+        assert_eq!(
+            directory.download_url("v1.33.3+k3s1", "k3s"),
+            "https://nexus.example.com/repository/k3s/v1.33.3+k3s1/k3s"
+        );
+        assert_eq!(
+            directory.list_url(),
+            "https://nexus.example.com/repository/k3s/"
+        );
+
+        assert!(K3sReleases::mirror("nexus.example.com/k3s", MirrorLayout::Directory).is_err());
+        assert!(
+            K3sReleases::mirror("ftp://nexus.example.com/k3s", MirrorLayout::Directory).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_github_versions() {
+        let body = r#"[
+            {"tag_name": "v1.34.0-rc1+k3s1", "prerelease": true, "draft": false},
+            {"tag_name": "v1.33.3+k3s1", "prerelease": false, "draft": false},
+            {"tag_name": "v1.32.7+k3s1", "prerelease": false, "draft": false}
+        ]"#;
+        assert_eq!(
+            K3sReleases::GitHub.parse_versions(body).unwrap(),
+            ["v1.33.3+k3s1", "v1.32.7+k3s1"]
+        );
+        assert!(K3sReleases::GitHub.parse_versions("[]").is_err());
+    }
+
+    #[test]
+    fn test_versions_in_listing() {
+        let html = r#"<html><body>
+            <a href="../">../</a>
+            <a href="v1.32.7%2Bk3s1/">v1.32.7+k3s1/</a>
+            <a href="v1.33.3%2Bk3s1/">v1.33.3+k3s1/</a>
+            <a href="v1.33.10+k3s2/">v1.33.10+k3s2/</a>
+            <a href="v1.34.0-rc1+k3s1/">v1.34.0-rc1+k3s1/</a>
+            <a href="values.txt">values.txt</a>
+        </body></html>"#;
+        assert_eq!(
+            versions_in_listing(html),
+            ["v1.33.10+k3s2", "v1.33.3+k3s1", "v1.32.7+k3s1"]
+        );
+    }
+
+    #[test]
+    fn test_is_release_name() {
+        assert!(is_release_name("v1.33.3+k3s1"));
+        assert!(is_release_name("v1.33.10+k3s12"));
+        assert!(!is_release_name("v1.34.0-rc1+k3s1"));
+        assert!(!is_release_name("v1.33.3+k3s1/.."));
+        assert!(!is_release_name("v1.33.3"));
+        assert!(!is_release_name("."));
+        assert!(!is_release_name(""));
+    }
+
+    #[test]
+    fn test_endpoints() {
+        let github = K3sReleases::GitHub.endpoints();
+        assert_eq!(github.len(), REQUIRED_ENDPOINTS.len());
+
+        let mirror =
+            K3sReleases::mirror("https://nexus.example.com/k3s", MirrorLayout::Directory).unwrap();
+        let endpoints = mirror.endpoints();
+        assert_eq!(endpoints[0].1, "https://nexus.example.com/k3s");
+        assert!(endpoints.iter().all(|(_, url)| !is_github(url)));
+        assert!(endpoints
+            .iter()
+            .any(|(_, url)| url == "https://update.k3s.io"));
+        assert_eq!(mirror.domain(), "nexus.example.com");
+    }
+}
@@ -3,6 +3,7 @@
 use anyhow::{anyhow, Context, Result};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::hash::BuildHasher;
 use std::io::Read;
 use std::path::Path;
 use thiserror::Error;
@@ -107,7 +108,10 @@ pub fn verify_file(path: &Path, expected_hash: &str) -> Result<()> {
 }
 
 /// Verify a file against a checksums map.
-pub fn verify_file_from_checksums(path: &Path, checksums: &HashMap<String, String>) -> Result<()> {
+pub fn verify_file_from_checksums<S: BuildHasher>(
+    path: &Path,
+    checksums: &HashMap<String, String, S>,
+) -> Result<()> {
     let filename = path
         .file_name()
         .ok_or_else(|| anyhow!("Invalid file path"))?
@@ -1,12 +1,164 @@
-//! Download progress display utilities.
+//! Download progress display and resumable download utilities.
+//!
+//! A download is written to `<file>.part`, with the URL and the server's
+//! validators (`ETag`, `Last-Modified`) in `<file>.part.json`. If the
+//! connection drops, the next attempt resumes with `Range`/`If-Range`, and
+//! the `.part` file is renamed into place only once it is complete.
+//!
+//! Transient failures (timeouts, connection resets, 5xx responses) are retried
+//! with jittered exponential backoff according to a [`RetryPolicy`].
 
+use crate::client::http::HttpClientError;
 use anyhow::{Context, Result};
 use futures_util::StreamExt;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use reqwest::header::{
+    HeaderMap, HeaderValue, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE,
+};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
 use std::fs;
+use std::hash::{BuildHasher, Hasher};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
 use tokio::io::AsyncWriteExt;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
+
+/// Suffix of a file that is still being downloaded
+const PART_SUFFIX: &str = ".part";
+
+/// Suffix of the metadata kept beside a `.part` file
+const PART_METADATA_SUFFIX: &str = ".part.json";
+
+#[derive(Error, Debug)]
+pub enum DownloadError {
+    #[error("Download of {url} failed: HTTP {status}")]
+    Status { url: String, status: StatusCode },
+}
+
+impl DownloadError {
+    /// Whether the server reported that the file does not exist
+    #[must_use]
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::Status { status, .. } if *status == StatusCode::NOT_FOUND)
+    }
+
+    /// Whether the server may succeed if asked again (5xx, timeout, rate limit)
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Status { status, .. } => {
+                status.is_server_error()
+                    || *status == StatusCode::REQUEST_TIMEOUT
+                    || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+        }
+    }
+}
+
+/// Upper bound for a single backoff delay
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// How often and how patiently to retry a failed download
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub retries: u32,
+    /// Delay before the first retry; doubled for each further retry
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            base_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `attempt` (starting at 1), scaled by
+    /// `jitter` in `[0, 1]` to between half and all of the exponential delay
+    #[must_use]
+    pub fn delay(&self, attempt: u32, jitter: f64) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(MAX_RETRY_DELAY);
+        exponential.mul_f64(0.5 + jitter.clamp(0.0, 1.0) / 2.0)
+    }
+
+    /// Delay before retry number `attempt`, with random jitter so parallel
+    /// downloads do not retry in lockstep
+    #[must_use]
+    pub fn next_delay(&self, attempt: u32) -> Duration {
+        // RandomState is randomly seeded, which is plenty for jitter
+        let random = std::collections::hash_map::RandomState::new()
+            .build_hasher()
+            .finish();
+        #[allow(clippy::cast_precision_loss)] // Only used as a fraction
+        let jitter = random as f64 / u64::MAX as f64;
+        self.delay(attempt, jitter)
+    }
+}
+
+/// Whether a failed download is worth retrying: timeouts, refused or dropped
+/// connections and 5xx responses are; 404s, DNS failures, invalid URLs,
+/// certificate and file errors are not
+#[must_use]
+pub fn is_retryable(error: &anyhow::Error) -> bool {
+    for cause in error.chain() {
+        if let Some(e) = cause.downcast_ref::<DownloadError>() {
+            return e.is_retryable();
+        }
+        if let Some(e) = cause.downcast_ref::<HttpClientError>() {
+            return matches!(
+                e,
+                HttpClientError::ConnectionRefused | HttpClientError::Timeout(_)
+            );
+        }
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            return is_transient(e);
+        }
+    }
+    false
+}
+
+fn is_transient(error: &reqwest::Error) -> bool {
+    if error.is_builder() {
+        return false;
+    }
+    if error.is_timeout() || error.is_body() {
+        return true;
+    }
+    // Connect errors include DNS failures, which retrying will not fix
+    if error.is_connect() {
+        return has_transient_io_error(error);
+    }
+    // The connection was dropped while sending the request or reading headers
+    error.is_request()
+}
+
+fn has_transient_io_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    use std::io::ErrorKind;
+
+    let mut source = Some(error);
+    while let Some(e) = source {
+        if let Some(io) = e.downcast_ref::<std::io::Error>() {
+            return matches!(
+                io.kind(),
+                ErrorKind::ConnectionRefused
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::TimedOut
+            );
+        }
+        source = e.source();
+    }
+    false
+}
 
 /// Context for managing multiple concurrent downloads with progress bars.
 pub struct DownloadManager {
@@ -14,6 +166,7 @@ pub struct DownloadManager {
 }
 
 impl DownloadManager {
+    #[must_use]
     pub fn new() -> Self {
         Self {
             multi_progress: MultiProgress::new(),
@@ -21,6 +174,7 @@ impl DownloadManager {
     }
 
     /// Create a new progress bar for a download.
+    #[must_use]
     pub fn add_download(&self, display_name: &str) -> ProgressBar {
         let pb = self.multi_progress.add(ProgressBar::new(0));
         pb.set_message(display_name.to_string());
@@ -45,6 +199,11 @@ impl DownloadManager {
         pb.finish_with_message(format!("\u{2714} {message}")); // ✔
     }
 
+    /// Show that a download failed and is retried after `delay`.
+    pub fn show_retry(pb: &ProgressBar, name: &str, attempt: u32, retries: u32, delay: Duration) {
+        pb.set_message(format!("{name} (retry {attempt}/{retries} in {delay:.0?})"));
+    }
+
     /// Mark a progress bar as failed.
     pub fn finish_error(pb: &ProgressBar, message: &str) {
         pb.set_style(
@@ -85,18 +244,28 @@ pub fn check_existing_file(path: &Path, progress: Option<&ProgressBar>) -> Optio
 /// Stream a response body to a file with progress tracking.
 ///
 /// This function streams the response data to the file in chunks,
-/// updating the progress bar as data is written.
+/// updating the progress bar as data is written. With a non-zero `offset`
+/// the body is appended to the `offset` bytes already in the file.
 pub async fn stream_to_file(
     response: reqwest::Response,
     path: &Path,
+    offset: u64,
     progress: Option<&ProgressBar>,
 ) -> Result<()> {
-    let mut file = tokio::fs::File::create(path)
-        .await
-        .with_context(|| format!("Failed to create file: {}", path.display()))?;
+    let mut file = if offset > 0 {
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .await
+            .with_context(|| format!("Failed to open file: {}", path.display()))?
+    } else {
+        tokio::fs::File::create(path)
+            .await
+            .with_context(|| format!("Failed to create file: {}", path.display()))?
+    };
 
     let mut stream = response.bytes_stream();
-    let mut downloaded: u64 = 0;
+    let mut downloaded: u64 = offset;
 
     while let Some(chunk) = stream.next().await {
         let chunk = chunk.with_context(|| format!("Error downloading to {}", path.display()))?;
@@ -132,6 +301,139 @@ pub fn cleanup_partial_download(path: &Path) {
     }
 }
 
+/// What is known about a partially downloaded file, so a later run can
+/// resume it with `Range`/`If-Range`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialDownload {
+    /// URL the partial file was downloaded from
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// Size of the complete file, if the server reported it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_size: Option<u64>,
+}
+
+impl PartialDownload {
+    /// Record the validators of a full (`200 OK`) response for `url`
+    #[must_use]
+    pub fn from_response(url: &str, response: &reqwest::Response) -> Self {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v: &HeaderValue| v.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            url: url.to_string(),
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            total_size: response.content_length(),
+        }
+    }
+
+    /// The `If-Range` validator: a strong `ETag`, else `Last-Modified`.
+    ///
+    /// Weak `ETag`s are not allowed in `If-Range`.
+    #[must_use]
+    pub fn validator(&self) -> Option<&str> {
+        self.etag
+            .as_deref()
+            .filter(|etag| !etag.starts_with("W/"))
+            .or(self.last_modified.as_deref())
+    }
+
+    /// `Range` (and `If-Range`, when there is a validator) headers to fetch
+    /// the rest of the file after `offset` bytes
+    #[must_use]
+    pub fn resume_headers(&self, offset: u64) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Ok(range) = HeaderValue::from_str(&format!("bytes={offset}-")) {
+            headers.insert(RANGE, range);
+        }
+        if let Some(validator) = self.validator().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(IF_RANGE, validator);
+        }
+        headers
+    }
+}
+
+/// Path of the in-progress download for `path`
+#[must_use]
+pub fn part_path(path: &Path) -> PathBuf {
+    with_suffix(path, PART_SUFFIX)
+}
+
+/// Whether `name` is an in-progress download or its saved metadata
+#[must_use]
+pub fn is_partial_download(name: &str) -> bool {
+    name.ends_with(PART_SUFFIX) || name.ends_with(PART_METADATA_SUFFIX)
+}
+
+fn part_metadata_path(path: &Path) -> PathBuf {
+    with_suffix(path, PART_METADATA_SUFFIX)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// The number of bytes already downloaded for `path` from `url`, with the
+/// saved metadata.
+///
+/// Partial files left by a download from another URL are discarded.
+#[must_use]
+pub fn resumable_download(path: &Path, url: &str) -> Option<(u64, PartialDownload)> {
+    let size = fs::metadata(part_path(path)).ok()?.len();
+    let partial: Option<PartialDownload> = fs::read_to_string(part_metadata_path(path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+
+    match partial {
+        Some(partial) if partial.url == url && size > 0 => Some((size, partial)),
+        _ => {
+            debug!("Discarding stale partial download of {}", path.display());
+            discard_partial_download(path);
+            None
+        }
+    }
+}
+
+/// Save the metadata for the in-progress download of `path`
+pub fn save_partial_download(path: &Path, partial: &PartialDownload) -> Result<()> {
+    let metadata_path = part_metadata_path(path);
+    fs::write(&metadata_path, serde_json::to_string_pretty(partial)?)
+        .with_context(|| format!("Failed to write {}", metadata_path.display()))
+}
+
+/// Move a completed `.part` file into place at `path`
+pub fn finish_partial_download(path: &Path) -> Result<()> {
+    fs::rename(part_path(path), path)
+        .with_context(|| format!("Failed to move download into place: {}", path.display()))?;
+    cleanup_partial_download(&part_metadata_path(path));
+    Ok(())
+}
+
+/// Remove the `.part` file and metadata for `path`, so the next download starts over
+pub fn discard_partial_download(path: &Path) {
+    cleanup_partial_download(&part_path(path));
+    cleanup_partial_download(&part_metadata_path(path));
+}
+
+/// The first byte position in a `Content-Range` header such as `bytes 100-999/1000`
+#[must_use]
+pub fn content_range_start(headers: &HeaderMap) -> Option<u64> {
+    let range = headers.get(CONTENT_RANGE)?.to_str().ok()?;
+    let (start, _) = range.strip_prefix("bytes ")?.split_once('-')?;
+    start.trim().parse().ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,4 +450,130 @@ mod tests {
         let result = check_existing_file(Path::new("/nonexistent/file"), None);
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_partial_download_validator() {
+        let mut partial = PartialDownload {
+            url: "https://example.com/k3s".to_string(),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+            total_size: Some(100),
+        };
+        assert_eq!(partial.validator(), Some("\"abc\""));
+
+        let headers = partial.resume_headers(40);
+        assert_eq!(headers[RANGE], "bytes=40-");
+        assert_eq!(headers[IF_RANGE], "\"abc\"");
+
+        // Weak ETags cannot be used with If-Range
+        partial.etag = Some("W/\"abc\"".to_string());
+        assert_eq!(partial.validator(), Some("Wed, 21 Oct 2015 07:28:00 GMT"));
+
+        partial.last_modified = None;
+        let headers = partial.resume_headers(40);
+        assert!(headers.contains_key(RANGE));
+        assert!(!headers.contains_key(IF_RANGE));
+    }
+
+    #[test]
+    fn test_resumable_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("k3s");
+        let url = "https://example.com/k3s";
+        assert!(resumable_download(&path, url).is_none());
+
+        let partial = PartialDownload {
+            url: url.to_string(),
+            etag: None,
+            last_modified: None,
+            total_size: Some(10),
+        };
+        fs::write(part_path(&path), b"12345").unwrap();
+        save_partial_download(&path, &partial).unwrap();
+        assert_eq!(part_path(&path), dir.path().join("k3s.part"));
+
+        assert_eq!(resumable_download(&path, url), Some((5, partial)));
+
+        // A partial file from another URL is discarded
+        assert!(resumable_download(&path, "https://mirror.example.com/k3s").is_none());
+        assert!(!part_path(&path).exists());
+        assert!(!part_metadata_path(&path).exists());
+    }
+
+    #[test]
+    fn test_finish_partial_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("k3s");
+        fs::write(part_path(&path), b"complete").unwrap();
+        fs::write(part_metadata_path(&path), "{}").unwrap();
+
+        finish_partial_download(&path).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"complete");
+        assert!(!part_path(&path).exists());
+        assert!(!part_metadata_path(&path).exists());
+    }
+
+    #[test]
+    fn test_content_range_start() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(content_range_start(&headers), None);
+        headers.insert(
+            CONTENT_RANGE,
+            HeaderValue::from_static("bytes 100-999/1000"),
+        );
+        assert_eq!(content_range_start(&headers), Some(100));
+        headers.insert(CONTENT_RANGE, HeaderValue::from_static("bytes */1000"));
+        assert_eq!(content_range_start(&headers), None);
+    }
+
+    #[test]
+    fn test_retry_policy_delay() {
+        let policy = RetryPolicy {
+            retries: 5,
+            base_delay: Duration::from_secs(2),
+        };
+        assert_eq!(policy.delay(1, 1.0), Duration::from_secs(2));
+        assert_eq!(policy.delay(2, 1.0), Duration::from_secs(4));
+        assert_eq!(policy.delay(3, 0.0), Duration::from_secs(4));
+        assert_eq!(policy.delay(30, 1.0), MAX_RETRY_DELAY);
+
+        let delay = policy.next_delay(2);
+        assert!(delay >= Duration::from_secs(2) && delay <= Duration::from_secs(4));
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let status = |status| -> anyhow::Error {
+            DownloadError::Status {
+                url: "https://example.com/k3s".to_string(),
+                status,
+            }
+            .into()
+        };
+        assert!(is_retryable(&status(StatusCode::BAD_GATEWAY)));
+        assert!(is_retryable(&status(StatusCode::TOO_MANY_REQUESTS)));
+        assert!(!is_retryable(&status(StatusCode::NOT_FOUND)));
+        assert!(!is_retryable(&status(StatusCode::FORBIDDEN)));
+
+        let timeout = anyhow::Error::from(HttpClientError::Timeout("slow".to_string()));
+        assert!(is_retryable(&timeout.context("Download failed")));
+        let certificate = anyhow::Error::from(HttpClientError::CertificateError {
+            domain: "github.com".to_string(),
+            reason: "self signed".to_string(),
+        });
+        assert!(!is_retryable(&certificate));
+
+        let io = anyhow::Error::from(std::io::Error::other("disk full"));
+        assert!(!is_retryable(&io));
+
+        // A malformed mirror URL fails the same way every time
+        let builder = reqwest::Client::new()
+            .get("http://[::1/k3s")
+            .build()
+            .unwrap_err();
+        assert!(builder.is_builder());
+        assert!(!is_retryable(
+            &anyhow::Error::new(builder).context("Download failed")
+        ));
+    }
 }